@@ -3,7 +3,9 @@
 use std::path::PathBuf;
 
 use crate::{
-    compilers::protobuf_compiler::ProtobufCompiler, os::types::OSManager, parser::types::Lang,
+    compilers::protobuf_compiler::ProtobufCompiler,
+    os::types::OSManager,
+    parser::types::{Lang, Project},
 };
 
 /// Parameters shared across all compiler implementations.
@@ -20,6 +22,55 @@ pub struct CompilerParams {
     pub lang: Lang,
 }
 
+/// What a protoc output generates, which decides its default folder.
+#[derive(Debug, Default, PartialEq, Clone, Copy)]
+pub enum OutputKind {
+    /// Additional output written to `compiled_proto_folder`
+    #[default]
+    Extra,
+    /// Message types, written to the project's messages folder
+    Messages,
+    /// gRPC service code, written to the project's gRPC folder
+    Grpc,
+}
+
+/// A single output requested from `protoc`.
+///
+/// Every protoc generator, built-in or plugin, is driven through the same
+/// flags: `--<generator>_out=<folder>`, `--<generator>_opt=<options>` and,
+/// when the `protoc-gen-<generator>` executable is not in PATH,
+/// `--plugin=protoc-gen-<generator>=<path>`.
+#[derive(Debug, Default, Clone)]
+pub struct ProtocOutput {
+    /// Generator name (e.g., "go", "csharp", "grpc", "prost")
+    pub generator: String,
+    /// Parameters passed to the generator through `--<generator>_opt`
+    pub options: Vec<String>,
+    /// Path to the `protoc-gen-<generator>` executable, if it must be given explicitly
+    pub plugin_path: Option<String>,
+    /// What this generator produces
+    pub kind: OutputKind,
+    /// Output folder for this generator (defaults to the folder of its kind)
+    pub out_folder: Option<String>,
+}
+
+impl ProtocOutput {
+    /// Returns the protoc output flag for this generator (e.g., "--go_out").
+    pub fn out_flag(&self) -> String {
+        format!("--{}_out", self.generator)
+    }
+
+    /// Returns the folder this generator writes to for the given project.
+    pub fn folder<'a>(&'a self, project: &'a Project) -> &'a String {
+        match (&self.out_folder, self.kind) {
+            (Some(out_folder), _) => out_folder,
+            (None, OutputKind::Messages) => project.messages_folder(),
+            (None, OutputKind::Grpc) => project.grpc_folder(),
+            (None, OutputKind::Extra) => &project.compiled_proto_folder,
+        }
+    }
+}
+
 // NOTE: We need separate structs for each language because their compilation
 // processes may vary significantly. For example, Rust requires post-processing
 // to organize files into proper module structures.
@@ -45,6 +96,53 @@ pub struct RustCompiler {
     pub params: CompilerParams,
 }
 
+/// Python language compiler implementation.
+///
+/// This compiler rewrites the generated imports to package-relative form and
+/// creates `__init__.py` files so the output folder is importable as a package.
+pub struct PythonCompiler {
+    /// Shared compiler parameters
+    pub params: CompilerParams,
+}
+
+/// TypeScript language compiler implementation.
+///
+/// This compiler writes an `index.ts` barrel for each proto package after
+/// generation, so every package can be imported as a single module.
+pub struct TypeScriptCompiler {
+    /// Shared compiler parameters
+    pub params: CompilerParams,
+}
+
+/// Java language compiler implementation.
+pub struct JavaCompiler {
+    /// Shared compiler parameters
+    pub params: CompilerParams,
+}
+
+/// Kotlin language compiler implementation.
+///
+/// Kotlin code generated by protoc builds on top of the Java classes, so this
+/// compiler emits both along with the Java gRPC stubs.
+pub struct KotlinCompiler {
+    /// Shared compiler parameters
+    pub params: CompilerParams,
+}
+
+/// C++ language compiler implementation.
+///
+/// This compiler can also write a CMake fragment listing every generated source.
+pub struct CppCompiler {
+    /// Shared compiler parameters
+    pub params: CompilerParams,
+}
+
+/// Compiler running only the generators declared in the project configuration.
+pub struct CustomCompiler {
+    /// Shared compiler parameters
+    pub params: CompilerParams,
+}
+
 /// Converts compiler parameters into the appropriate language-specific compiler.
 ///
 /// This implementation uses the language field to determine which compiler
@@ -56,6 +154,12 @@ impl From<CompilerParams> for Box<dyn ProtobufCompiler> {
             Lang::GoLang => Box::new(GoCompiler { params }),
             Lang::DotNet => Box::new(DotNetCompiler { params }),
             Lang::Rust => Box::new(RustCompiler { params }),
+            Lang::Python => Box::new(PythonCompiler { params }),
+            Lang::TypeScript => Box::new(TypeScriptCompiler { params }),
+            Lang::Java => Box::new(JavaCompiler { params }),
+            Lang::Kotlin => Box::new(KotlinCompiler { params }),
+            Lang::Cpp => Box::new(CppCompiler { params }),
+            Lang::Custom => Box::new(CustomCompiler { params }),
         }
     }
 }
@@ -0,0 +1,214 @@
+//! C++ language compiler implementation with optional CMake integration.
+
+use std::{collections::BTreeSet, path::PathBuf};
+
+use log::debug;
+
+use crate::{
+    compilers::{
+        langs_compilers::compiler_types::{CppCompiler, OutputKind, ProtocOutput},
+        protobuf_compiler::{CompilerProperties, ProtobufCompiler},
+        shared::{grpc_services_options, proto_import_name, relative_path},
+    },
+    parser::types::Project,
+};
+
+impl CompilerProperties for CppCompiler {
+    fn os_manager(&self) -> &dyn crate::os::types::OSManager {
+        self.params.os_manager.as_ref()
+    }
+
+    fn input_file_path(&self) -> &PathBuf {
+        &self.params.input_file_path
+    }
+}
+
+/// Required dependencies for C++ proto compilation.
+///
+/// These tools must be installed and available:
+/// - `protoc`: Protocol Buffers compiler
+static CPP_DEPS: [&str; 1] = ["protoc"];
+
+/// Version flags for checking each dependency.
+static CPP_VERSION_FLAGS: [&str; 1] = ["--version"];
+
+/// Protoc generator for C++ message types (`--cpp_out`).
+static CPP_MESSAGES_GENERATOR: &str = "cpp";
+
+/// Protoc generator for C++ gRPC service code (`--grpc_out`), backed by the
+/// `grpc_cpp_plugin` registered as `protoc-gen-grpc`.
+static CPP_GRPC_GENERATOR: &str = "grpc";
+
+/// Name of the gRPC C++ plugin executable.
+static CPP_PLUGIN_NAME: &str = "grpc_cpp_plugin";
+
+/// Suffix protoc adds to the name of the message files (e.g., `auth.pb.cc`).
+static CPP_MESSAGES_SUFFIX: &str = ".pb";
+
+/// Suffix `grpc_cpp_plugin` adds to the name of the service files (e.g., `auth.grpc.pb.cc`).
+static CPP_GRPC_SUFFIX: &str = ".grpc.pb";
+
+/// Extension of the generated sources.
+static CPP_SOURCE_EXTENSION: &str = ".cc";
+
+/// Extension of the generated headers.
+static CPP_HEADER_EXTENSION: &str = ".h";
+
+/// Filename of the generated CMake fragment.
+static CMAKE_FRAGMENT_FILENAME: &str = "protoweld_generated.cmake";
+
+impl ProtobufCompiler for CppCompiler {
+    /// Compiles proto files to C++ code.
+    ///
+    /// This implementation uses protoc's built-in `--cpp_out` for message types
+    /// and `grpc_cpp_plugin` for gRPC services. The plugin is taken from the
+    /// project's `plugin_path` field or, if absent, looked up in PATH.
+    ///
+    /// # Arguments
+    ///
+    /// * `project` - Project configuration with C++-specific settings
+    ///
+    /// # Returns
+    ///
+    /// * `Ok(())` - C++ code generated successfully
+    /// * `Err(String)` - Error message if compilation fails
+    ///
+    /// # Generated Output
+    ///
+    /// Generates `.pb.h`/`.pb.cc` files for message types in `messages_out` and
+    /// `.grpc.pb.h`/`.grpc.pb.cc` files for gRPC services in `grpc_out` (both
+    /// defaulting to `compiled_proto_folder`). gRPC code is skipped when
+    /// `messages_only` is set. When `cpp_options.cmake_fragment` is enabled,
+    /// also writes `protoweld_generated.cmake` in the messages folder defining:
+    /// - `PROTOWELD_GENERATED_SOURCES`: the `.cc` files of `associated_proto_files`
+    /// - `PROTOWELD_GENERATED_HEADERS`: the `.h` files of `associated_proto_files`
+    /// - `PROTOWELD_GENERATED_INCLUDE_DIRS`: folders to add to the include path
+    fn compile_project(&self, project: &Project) -> Result<(), String> {
+        let mut outputs: Vec<ProtocOutput> = vec![ProtocOutput {
+            generator: String::from(CPP_MESSAGES_GENERATOR),
+            kind: OutputKind::Messages,
+            ..Default::default()
+        }];
+
+        if project.generates_grpc() {
+            let plugin_path: String = match &project.plugin_path {
+                Some(plugin_path) => self.plugin_executable(plugin_path)?,
+                None => match self.os_manager().find_executable(CPP_PLUGIN_NAME) {
+                    Some(plugin_path) => plugin_path.to_string_lossy().to_string(),
+                    None => {
+                        return Err(format!(
+                            "The plugin {} must have a path in plugin_path option or be in PATH",
+                            CPP_PLUGIN_NAME
+                        ))
+                    }
+                },
+            };
+
+            outputs.push(ProtocOutput {
+                generator: String::from(CPP_GRPC_GENERATOR),
+                // grpc_cpp_plugin always generates both stubs and services
+                options: grpc_services_options(project, CPP_PLUGIN_NAME, None, None),
+                kind: OutputKind::Grpc,
+                plugin_path: Some(plugin_path),
+                ..Default::default()
+            });
+        }
+
+        self.assemble_compilation(
+            project,
+            CPP_DEPS.to_vec(),
+            CPP_VERSION_FLAGS.to_vec(),
+            outputs,
+        )?;
+
+        if project.cpp_options.cmake_fragment {
+            self.write_cmake_fragment(project)?;
+        }
+
+        Ok(())
+    }
+}
+
+impl CppCompiler {
+    /// Writes `protoweld_generated.cmake` in the messages folder.
+    ///
+    /// The files are derived from `associated_proto_files` rather than listed
+    /// from the output folders, since protoc never removes the output of proto
+    /// files that are no longer compiled. Each proto file is named after its
+    /// path relative to the include root containing it, so `security/auth.proto`
+    /// yields `security/auth.pb.cc` and, with gRPC, `security/auth.grpc.pb.cc`
+    /// in `grpc_out`.
+    ///
+    /// Paths are relative to `CMAKE_CURRENT_LIST_DIR`, so the fragment keeps
+    /// working if the output folders are moved together or included from
+    /// another project.
+    fn write_cmake_fragment(&self, project: &Project) -> Result<(), String> {
+        let base_path: PathBuf = project.messages_folder().into();
+        let grpc_path: PathBuf = project.grpc_folder().into();
+        let mut folders: BTreeSet<PathBuf> = BTreeSet::from([base_path.clone()]);
+        if project.generates_grpc() {
+            folders.insert(grpc_path.clone());
+        }
+
+        let to_cmake_path = |path: PathBuf| -> String {
+            path.components()
+                .map(|component| component.as_os_str().to_string_lossy().to_string())
+                .collect::<Vec<String>>()
+                .join("/")
+        };
+
+        let mut sources: Vec<String> = Vec::new();
+        let mut headers: Vec<String> = Vec::new();
+        let include_dirs: Vec<String> = folders
+            .iter()
+            .map(|folder| to_cmake_path(relative_path(&base_path, folder)))
+            .collect();
+
+        let include_roots: Vec<PathBuf> = project.include_roots();
+        for proto in project.associated_proto_files.iter() {
+            let stem: String =
+                to_cmake_path(proto_import_name(proto, &include_roots).with_extension(""));
+
+            let mut outputs: Vec<(&PathBuf, &str)> = vec![(&base_path, CPP_MESSAGES_SUFFIX)];
+            if project.generates_grpc() {
+                outputs.push((&grpc_path, CPP_GRPC_SUFFIX));
+            }
+
+            for (folder, suffix) in outputs {
+                let file: PathBuf = folder.join(format!("{}{}", stem, suffix));
+                let relative: String = to_cmake_path(relative_path(&base_path, &file));
+                sources.push(format!("{}{}", relative, CPP_SOURCE_EXTENSION));
+                headers.push(format!("{}{}", relative, CPP_HEADER_EXTENSION));
+            }
+        }
+
+        let cmake_list = |name: &str, paths: &[String]| -> String {
+            let mut list = format!("set({}\n", name);
+            for path in paths.iter() {
+                match path.is_empty() {
+                    true => list.push_str("    ${CMAKE_CURRENT_LIST_DIR}\n"),
+                    false => list.push_str(&format!("    ${{CMAKE_CURRENT_LIST_DIR}}/{}\n", path)),
+                }
+            }
+            list.push_str(")\n");
+            list
+        };
+
+        let content: String = [
+            String::from("# Generated by protoweld. Do not edit.\n"),
+            cmake_list("PROTOWELD_GENERATED_SOURCES", &sources),
+            cmake_list("PROTOWELD_GENERATED_HEADERS", &headers),
+            cmake_list("PROTOWELD_GENERATED_INCLUDE_DIRS", &include_dirs),
+        ]
+        .join("\n");
+
+        let fragment_file: PathBuf = base_path.join(CMAKE_FRAGMENT_FILENAME);
+        debug!(
+            "Writing CMake fragment {} with {} sources",
+            fragment_file.display(),
+            sources.len()
+        );
+
+        self.os_manager().write_new_file(&fragment_file, content)
+    }
+}
@@ -0,0 +1,65 @@
+//! Compiler for projects driven only by configured generators.
+
+use std::path::PathBuf;
+
+use crate::{
+    compilers::{
+        langs_compilers::compiler_types::CustomCompiler,
+        protobuf_compiler::{CompilerProperties, ProtobufCompiler},
+    },
+    parser::types::Project,
+};
+
+impl CompilerProperties for CustomCompiler {
+    fn os_manager(&self) -> &dyn crate::os::types::OSManager {
+        self.params.os_manager.as_ref()
+    }
+
+    fn input_file_path(&self) -> &PathBuf {
+        &self.params.input_file_path
+    }
+}
+
+/// Required dependencies for custom proto compilation.
+///
+/// These tools must be installed and available:
+/// - `protoc`: Protocol Buffers compiler
+///
+/// Plugins are checked separately from the project's `generators`.
+static CUSTOM_DEPS: [&str; 1] = ["protoc"];
+
+/// Version flags for checking each dependency.
+static CUSTOM_VERSION_FLAGS: [&str; 1] = ["--version"];
+
+impl ProtobufCompiler for CustomCompiler {
+    /// Compiles proto files with the generators declared in the configuration.
+    ///
+    /// Every entry in the project's `generators` field becomes a
+    /// `--<name>_out` flag (plus `--<name>_opt` and `--plugin` when needed),
+    /// so any `protoc-gen-*` plugin can be used without a dedicated compiler.
+    ///
+    /// # Arguments
+    ///
+    /// * `project` - Project configuration with at least one generator
+    ///
+    /// # Returns
+    ///
+    /// * `Ok(())` - Code generated successfully
+    /// * `Err(String)` - Error message if no generator is declared or compilation fails
+    fn compile_project(&self, project: &Project) -> Result<(), String> {
+        if project.generators.is_empty() {
+            return Err(format!(
+                "Project {} uses the Custom lang but declares no generators",
+                project.path
+            ));
+        }
+
+        // The declared generators are merged in by assemble_compilation
+        self.assemble_compilation(
+            project,
+            CUSTOM_DEPS.to_vec(),
+            CUSTOM_VERSION_FLAGS.to_vec(),
+            Vec::new(),
+        )
+    }
+}
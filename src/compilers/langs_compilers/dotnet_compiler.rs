@@ -1,15 +1,21 @@
 //! .NET (C#) language compiler implementation.
 
-use std::path::PathBuf;
+use std::{cmp::Ordering, env::consts, ops::Range, path::PathBuf};
 
-use crate::compilers::{
-    langs_compilers::compiler_types::DotNetCompiler,
-    protobuf_compiler::{CompilerProperties, ProtobufCompiler},
+use log::{debug, info, warn};
+
+use crate::{
+    compilers::{
+        langs_compilers::compiler_types::{DotNetCompiler, OutputKind, ProtocOutput},
+        protobuf_compiler::{CompilerProperties, ProtobufCompiler},
+        shared::{compare_versions, grpc_services_options},
+    },
+    parser::types::{GrpcServices, Project},
 };
 
 impl CompilerProperties for DotNetCompiler {
-    fn os_manager(&self) -> &Box<dyn crate::os::types::OSManager> {
-        &self.params.os_manager
+    fn os_manager(&self) -> &dyn crate::os::types::OSManager {
+        self.params.os_manager.as_ref()
     }
 
     fn input_file_path(&self) -> &PathBuf {
@@ -22,26 +28,72 @@ impl CompilerProperties for DotNetCompiler {
 /// These tools must be installed and available:
 /// - `protoc`: Protocol Buffers compiler
 /// - `dotnet`: .NET SDK
-static DOTNET_DEPS: [&'static str; 2] = ["protoc", "dotnet"];
+static DOTNET_DEPS: [&str; 2] = ["protoc", "dotnet"];
 
 /// Version flags for checking each dependency.
-static DOTNET_VERSION_FLAGS: [&'static str; 2] = ["--version", "--version"];
+static DOTNET_VERSION_FLAGS: [&str; 2] = ["--version", "--version"];
 
-/// Protoc flag for generating C# message types.
-static DOTNET_MESSAGES_OUT_ARGUMENT: &'static str = "--csharp_out";
+/// Protoc generator for C# message types (`--csharp_out`).
+static DOTNET_MESSAGES_GENERATOR: &str = "csharp";
 
-/// Protoc flag for generating C# gRPC service code.
-static DOTNET_GRPC_OUT_ARGUMENT: &'static str = "--grpc_out";
+/// Protoc generator for C# gRPC service code (`--grpc_out`), backed by the
+/// `grpc_csharp_plugin` registered as `protoc-gen-grpc`.
+static DOTNET_GRPC_GENERATOR: &str = "grpc";
 
 /// Name of the gRPC C# plugin (used for error messages).
-static DOTNET_PLUGIN_NAME: &'static str = "grpc_csharp_plugin";
+static DOTNET_PLUGIN_NAME: &str = "grpc_csharp_plugin";
+
+/// NuGet package shipping `grpc_csharp_plugin`, as named in the packages cache.
+static DOTNET_GRPC_TOOLS_PACKAGE: &str = "grpc.tools";
+
+/// Folder of the `Grpc.Tools` package holding the per-platform executables.
+static DOTNET_GRPC_TOOLS_FOLDER: &str = "tools";
+
+/// `grpc_csharp_plugin` options generating client stubs only.
+static DOTNET_CLIENT_ONLY_OPTIONS: [&str; 1] = ["no_server"];
+
+/// `grpc_csharp_plugin` options generating server bases only.
+static DOTNET_SERVER_ONLY_OPTIONS: [&str; 1] = ["no_client"];
+
+/// Generator option laying out files by namespace, relative to the given one.
+static DOTNET_BASE_NAMESPACE_OPTION: &str = "base_namespace";
+
+/// Extension of .NET project files.
+static DOTNET_CSPROJ_EXTENSION: &str = "csproj";
+
+/// Package with the protobuf runtime, required by the message types.
+static DOTNET_PROTOBUF_PACKAGE: &str = "Google.Protobuf";
+
+/// Package with the gRPC API, required by client stubs and server bases.
+static DOTNET_GRPC_API_PACKAGE: &str = "Grpc.Core.Api";
+
+/// Package with the gRPC channel, required by client stubs.
+static DOTNET_GRPC_CLIENT_PACKAGE: &str = "Grpc.Net.Client";
+
+/// Closing tag of a .NET project file.
+static DOTNET_PROJECT_END_TAG: &str = "</Project>";
+
+/// Start of the `.csproj` element referencing a NuGet package.
+static DOTNET_PACKAGE_REFERENCE_TAG: &str = "<PackageReference";
+
+/// Closing tag of a `<PackageReference>` with child elements.
+static DOTNET_PACKAGE_REFERENCE_END_TAG: &str = "</PackageReference>";
+
+/// Version attribute of a `<PackageReference>`, up to its value.
+static DOTNET_VERSION_ATTRIBUTE: &str = "Version=\"";
+
+/// Opening tag of the version of a `<PackageReference>` with child elements.
+static DOTNET_VERSION_START_TAG: &str = "<Version>";
+
+/// Closing tag of the version of a `<PackageReference>` with child elements.
+static DOTNET_VERSION_END_TAG: &str = "</Version>";
 
 impl ProtobufCompiler for DotNetCompiler {
     /// Compiles proto files to C# code.
     ///
     /// This implementation uses `protoc` with `--csharp_out` for message types
-    /// and requires the `grpc_csharp_plugin` for gRPC services. The plugin path
-    /// must be specified in the project's `plugin_path` field.
+    /// and the `grpc_csharp_plugin` shipped by the `Grpc.Tools` NuGet package
+    /// for gRPC services.
     ///
     /// # Arguments
     ///
@@ -54,22 +106,418 @@ impl ProtobufCompiler for DotNetCompiler {
     ///
     /// # Requirements
     ///
-    /// The project configuration must include `plugin_path` pointing to the
-    /// `grpc_csharp_plugin` executable, typically found in:
-    /// `~/.nuget/packages/grpc.tools/<version>/tools/<platform>/grpc_csharp_plugin`
+    /// Unless `messages_only` is set, `grpc_csharp_plugin` is taken from, in order:
+    /// 1. The project's `plugin_path`
+    /// 2. The NuGet cache in `NUGET_PACKAGES`
+    /// 3. The default NuGet cache, `~/.nuget/packages`
+    ///
+    /// In the caches the plugin is found in
+    /// `grpc.tools/<version>/tools/<platform>/grpc_csharp_plugin`, using the
+    /// `dotnet_options.grpc_tools_version` version when set, or the highest installed one.
     ///
     /// # Generated Output
     ///
-    /// Generates `.cs` files for message types and gRPC services in the
-    /// specified `compiled_proto_folder`.
-    fn compile_project(&self, project: &crate::parser::types::Project) -> Result<(), String> {
+    /// Generates `.cs` files for message types in `messages_out` and for gRPC
+    /// services in `grpc_out` (both defaulting to `compiled_proto_folder`).
+    /// With `dotnet_options.base_namespace`, files are placed in folders
+    /// following their `csharp_namespace`, and with `dotnet_options.csproj` a
+    /// class library project is written in `compiled_proto_folder`.
+    fn compile_project(&self, project: &Project) -> Result<(), String> {
+        let namespace_options: Vec<String> = match &project.dotnet_options.base_namespace {
+            Some(base_namespace) => vec![format!(
+                "{}={}",
+                DOTNET_BASE_NAMESPACE_OPTION, base_namespace
+            )],
+            None => Vec::new(),
+        };
+
+        let mut outputs: Vec<ProtocOutput> = vec![ProtocOutput {
+            generator: String::from(DOTNET_MESSAGES_GENERATOR),
+            options: namespace_options.clone(),
+            kind: OutputKind::Messages,
+            ..Default::default()
+        }];
+
+        if project.generates_grpc() {
+            let plugin_path: String = match &project.plugin_path {
+                Some(plugin_path) => self.plugin_executable(plugin_path)?,
+                None => self.locate_grpc_plugin(project)?,
+            };
+
+            let mut grpc_options: Vec<String> = grpc_services_options(
+                project,
+                DOTNET_GRPC_GENERATOR,
+                Some(&DOTNET_CLIENT_ONLY_OPTIONS),
+                Some(&DOTNET_SERVER_ONLY_OPTIONS),
+            );
+            grpc_options.extend(namespace_options);
+
+            outputs.push(ProtocOutput {
+                generator: String::from(DOTNET_GRPC_GENERATOR),
+                options: grpc_options,
+                kind: OutputKind::Grpc,
+                plugin_path: Some(plugin_path),
+                ..Default::default()
+            });
+        }
+
         self.assemble_compilation(
             project,
             DOTNET_DEPS.to_vec(),
             DOTNET_VERSION_FLAGS.to_vec(),
-            DOTNET_MESSAGES_OUT_ARGUMENT,
-            DOTNET_GRPC_OUT_ARGUMENT,
-            Option::Some(DOTNET_PLUGIN_NAME),
-        )
+            outputs,
+        )?;
+
+        if project.dotnet_options.csproj {
+            self.write_csproj(project)?;
+        }
+
+        Ok(())
+    }
+}
+
+impl DotNetCompiler {
+    /// Finds `grpc_csharp_plugin` in the `Grpc.Tools` packages of the NuGet cache,
+    /// at `<cache>/grpc.tools/<version>/tools/<platform>/grpc_csharp_plugin`.
+    ///
+    /// The caches returned by [`OSManager::nuget_packages_folders`] are searched
+    /// for `dotnet_options.grpc_tools_version`, or for the highest installed
+    /// version when it isn't set.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error listing the searched locations when no plugin is found.
+    ///
+    /// [`OSManager::nuget_packages_folders`]: crate::os::types::OSManager::nuget_packages_folders
+    fn locate_grpc_plugin(&self, project: &Project) -> Result<String, String> {
+        let configured_version: Option<String> = project
+            .dotnet_options
+            .grpc_tools_version
+            .as_ref()
+            .map(|version| version.to_lowercase());
+        let platform: Option<&'static str> = grpc_tools_platform();
+        let plugin_name: String = format!("{}{}", DOTNET_PLUGIN_NAME, consts::EXE_SUFFIX);
+
+        let mut searched: Vec<String> = Vec::new();
+        let mut found: Option<(String, PathBuf)> = None;
+
+        for cache in self.os_manager().nuget_packages_folders() {
+            let package_folder: PathBuf = cache.join(DOTNET_GRPC_TOOLS_PACKAGE);
+            searched.push(
+                package_folder
+                    .join(configured_version.as_deref().unwrap_or("*"))
+                    .join(DOTNET_GRPC_TOOLS_FOLDER)
+                    .join(platform.unwrap_or("*"))
+                    .join(&plugin_name)
+                    .display()
+                    .to_string(),
+            );
+
+            let Some(platform) = platform else {
+                continue;
+            };
+            let Ok(version_folders) = self.os_manager().list_folders(&package_folder) else {
+                continue;
+            };
+
+            for version_folder in version_folders {
+                let version: String = version_folder
+                    .file_name()
+                    .map(|name| name.to_string_lossy().to_string())
+                    .unwrap_or_default();
+                if configured_version
+                    .as_ref()
+                    .is_some_and(|configured| *configured != version)
+                {
+                    continue;
+                }
+
+                let candidate: PathBuf = version_folder
+                    .join(DOTNET_GRPC_TOOLS_FOLDER)
+                    .join(platform)
+                    .join(&plugin_name);
+                let newer: bool = match &found {
+                    Some((found_version, _)) => {
+                        compare_versions(&version, found_version) == Ordering::Greater
+                    }
+                    None => true,
+                };
+                if newer && self.os_manager().is_file(&candidate) {
+                    found = Some((version, candidate));
+                }
+            }
+        }
+
+        match found {
+            Some((version, plugin_path)) => {
+                info!(
+                    "Using {} {} from the NuGet cache: {}",
+                    DOTNET_PLUGIN_NAME,
+                    version,
+                    plugin_path.display()
+                );
+                Ok(plugin_path.to_string_lossy().to_string())
+            }
+            None if platform.is_none() => Err(format!(
+                "{} is not shipped by Grpc.Tools for {} on {}, so it must be set in plugin_path",
+                DOTNET_PLUGIN_NAME,
+                consts::OS,
+                consts::ARCH
+            )),
+            None if searched.is_empty() => Err(format!(
+                "The NuGet cache could not be located to find {} (set NUGET_PACKAGES or plugin_path)",
+                DOTNET_PLUGIN_NAME
+            )),
+            None => Err(format!(
+                "{} was not found in the NuGet cache, install the Grpc.Tools package{} or set plugin_path. Searched:\n{}",
+                DOTNET_PLUGIN_NAME,
+                match &project.dotnet_options.grpc_tools_version {
+                    Some(version) => format!(" {}", version),
+                    None => String::new(),
+                },
+                searched
+                    .iter()
+                    .map(|path| format!("  - {}", path))
+                    .collect::<Vec<String>>()
+                    .join("\n")
+            )),
+        }
+    }
+
+    /// Writes a class library `.csproj` in `compiled_proto_folder`, or updates
+    /// the package references of the one already there.
+    ///
+    /// A new project is named after the folder (e.g., `Security.Protos/Security.Protos.csproj`).
+    /// It references `Google.Protobuf`, plus `Grpc.Core.Api` when gRPC code is
+    /// generated and `Grpc.Net.Client` when client stubs are. An existing project
+    /// only gets those references added or their versions updated, so manual
+    /// changes are kept.
+    fn write_csproj(&self, project: &Project) -> Result<(), String> {
+        let project_folder: PathBuf = PathBuf::from(&project.compiled_proto_folder);
+        let options = &project.dotnet_options;
+
+        let mut packages: Vec<(&'static str, &String)> =
+            vec![(DOTNET_PROTOBUF_PACKAGE, &options.protobuf_version)];
+        if project.generates_grpc() {
+            packages.push((DOTNET_GRPC_API_PACKAGE, &options.grpc_version));
+            if project.grpc_services != GrpcServices::Server {
+                packages.push((DOTNET_GRPC_CLIENT_PACKAGE, &options.grpc_version));
+            }
+        }
+
+        // SDK-style projects compile every .cs file below their folder
+        for folder in [project.messages_folder(), project.grpc_folder()] {
+            if !PathBuf::from(folder).starts_with(&project_folder) {
+                warn!(
+                    "{} is outside {}, so its files are not part of the generated .csproj",
+                    folder, project.compiled_proto_folder
+                );
+            }
+        }
+
+        let existing: Option<PathBuf> = self
+            .os_manager()
+            .list_files(&project_folder)?
+            .into_iter()
+            .find(|file| {
+                file.parent() == Some(project_folder.as_path())
+                    && file
+                        .extension()
+                        .is_some_and(|extension| extension == DOTNET_CSPROJ_EXTENSION)
+            });
+
+        let (csproj_path, content): (PathBuf, String) = match existing {
+            Some(csproj_path) => {
+                let current: String = self.os_manager().read_file(&csproj_path)?;
+                (csproj_path, update_package_references(&current, &packages))
+            }
+            None => {
+                let name: String = project_folder
+                    .file_name()
+                    .unwrap_or_default()
+                    .to_string_lossy()
+                    .to_string();
+                let references: String = packages
+                    .iter()
+                    .map(|(package, version)| package_reference(package, version))
+                    .collect::<Vec<String>>()
+                    .join("\n");
+
+                (
+                    project_folder.join(format!("{}.{}", name, DOTNET_CSPROJ_EXTENSION)),
+                    format!(
+                        "<Project Sdk=\"Microsoft.NET.Sdk\">\n\n  <PropertyGroup>\n    <TargetFramework>{}</TargetFramework>\n  </PropertyGroup>\n\n  <ItemGroup>\n{}\n  </ItemGroup>\n\n{}\n",
+                        options.target_framework, references, DOTNET_PROJECT_END_TAG
+                    ),
+                )
+            }
+        };
+
+        debug!("Writing {}", csproj_path.display());
+        self.os_manager().write_new_file(&csproj_path, content)
+    }
+}
+
+/// Formats a `PackageReference` element, indented for an `ItemGroup`.
+fn package_reference(package: &str, version: &str) -> String {
+    format!(
+        "    <PackageReference Include=\"{}\" Version=\"{}\" />",
+        package, version
+    )
+}
+
+/// Sets the version of the given package references in a `.csproj`, adding
+/// the missing ones in a new `ItemGroup`. Everything else is kept as is.
+fn update_package_references(content: &str, packages: &[(&'static str, &String)]) -> String {
+    let mut missing: Vec<String> = Vec::new();
+    let mut content: String = content.to_string();
+
+    for (package, version) in packages.iter() {
+        match package_version_range(&content, package) {
+            Some(Some(range)) => content.replace_range(range, version),
+            // Versions managed elsewhere (e.g., Directory.Packages.props) are left alone
+            Some(None) => debug!("{} has no version to update", package),
+            None => missing.push(package_reference(package, version)),
+        }
+    }
+
+    let mut lines: Vec<String> = content.lines().map(String::from).collect();
+    if !missing.is_empty() {
+        let end: usize = lines
+            .iter()
+            .rposition(|line| line.trim() == DOTNET_PROJECT_END_TAG)
+            .unwrap_or(lines.len());
+        let mut item_group: Vec<String> = vec![String::new(), String::from("  <ItemGroup>")];
+        item_group.extend(missing);
+        item_group.push(String::from("  </ItemGroup>"));
+        lines.splice(end..end, item_group);
+    }
+
+    lines.join("\n") + "\n"
+}
+
+/// Finds the version of a package reference in a `.csproj`, either in the
+/// `Version` attribute of `<PackageReference>` or in its `<Version>` element.
+///
+/// Returns `None` if the package isn't referenced, and `Some(None)` if its
+/// reference has no version.
+fn package_version_range(content: &str, package: &str) -> Option<Option<Range<usize>>> {
+    let include: String = format!("Include=\"{}\"", package);
+
+    let mut search_start: usize = 0;
+    let (tag_start, tag_end): (usize, usize) = loop {
+        let tag_start: usize =
+            search_start + content[search_start..].find(DOTNET_PACKAGE_REFERENCE_TAG)?;
+        let tag_end: usize = tag_start + content[tag_start..].find('>')? + 1;
+        if content[tag_start..tag_end].contains(&include) {
+            break (tag_start, tag_end);
+        }
+        search_start = tag_end;
+    };
+
+    let tag: &str = &content[tag_start..tag_end];
+    let attribute: Option<usize> = tag
+        .match_indices(DOTNET_VERSION_ATTRIBUTE)
+        .find(|(index, _)| tag[..*index].ends_with(char::is_whitespace))
+        .map(|(index, _)| tag_start + index + DOTNET_VERSION_ATTRIBUTE.len());
+    if let Some(value_start) = attribute {
+        return Some(
+            content[value_start..]
+                .find('"')
+                .map(|length| value_start..value_start + length),
+        );
+    }
+    if tag.ends_with("/>") {
+        return Some(None);
+    }
+
+    // Multi-line references declare their version as a child element
+    let element: &str = match content[tag_end..].find(DOTNET_PACKAGE_REFERENCE_END_TAG) {
+        Some(length) => &content[tag_end..tag_end + length],
+        None => return Some(None),
+    };
+    Some(element.find(DOTNET_VERSION_START_TAG).and_then(|index| {
+        let value_start: usize = tag_end + index + DOTNET_VERSION_START_TAG.len();
+        content[value_start..]
+            .find(DOTNET_VERSION_END_TAG)
+            .map(|length| value_start..value_start + length)
+    }))
+}
+
+/// Returns the folder of `Grpc.Tools` holding the executables for the current
+/// platform, or `None` if the package doesn't ship them.
+///
+/// macOS only has `macosx_x64` binaries, which Apple Silicon runs through Rosetta.
+fn grpc_tools_platform() -> Option<&'static str> {
+    match (consts::OS, consts::ARCH) {
+        ("linux", "x86_64") => Some("linux_x64"),
+        ("linux", "x86") => Some("linux_x86"),
+        ("linux", "aarch64") => Some("linux_arm64"),
+        ("macos", "x86_64" | "aarch64") => Some("macosx_x64"),
+        ("windows", "x86_64") => Some("windows_x64"),
+        ("windows", "x86") => Some("windows_x86"),
+        _ => None,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn updates_self_closing_package_references() {
+        let content: &str = "<Project Sdk=\"Microsoft.NET.Sdk\">\n  <ItemGroup>\n    <PackageReference Include=\"Google.Protobuf\" Version=\"3.20.0\" PrivateAssets=\"all\" />\n    <PackageReference Include=\"Newtonsoft.Json\" Version=\"13.0.1\" />\n  </ItemGroup>\n</Project>\n";
+        let version: String = String::from("3.27.1");
+
+        assert_eq!(
+            update_package_references(content, &[(DOTNET_PROTOBUF_PACKAGE, &version)]),
+            "<Project Sdk=\"Microsoft.NET.Sdk\">\n  <ItemGroup>\n    <PackageReference Include=\"Google.Protobuf\" Version=\"3.27.1\" PrivateAssets=\"all\" />\n    <PackageReference Include=\"Newtonsoft.Json\" Version=\"13.0.1\" />\n  </ItemGroup>\n</Project>\n"
+        );
+    }
+
+    #[test]
+    fn updates_multi_line_package_references() {
+        let content: &str = "<Project Sdk=\"Microsoft.NET.Sdk\">\n  <ItemGroup>\n    <PackageReference Include=\"Google.Protobuf\">\n      <Version>3.20.0</Version>\n      <PrivateAssets>all</PrivateAssets>\n    </PackageReference>\n  </ItemGroup>\n</Project>\n";
+        let version: String = String::from("3.27.1");
+
+        assert_eq!(
+            update_package_references(content, &[(DOTNET_PROTOBUF_PACKAGE, &version)]),
+            "<Project Sdk=\"Microsoft.NET.Sdk\">\n  <ItemGroup>\n    <PackageReference Include=\"Google.Protobuf\">\n      <Version>3.27.1</Version>\n      <PrivateAssets>all</PrivateAssets>\n    </PackageReference>\n  </ItemGroup>\n</Project>\n"
+        );
+    }
+
+    #[test]
+    fn keeps_package_references_without_versions() {
+        let content: &str = "<Project Sdk=\"Microsoft.NET.Sdk\">\n  <ItemGroup>\n    <PackageReference Include=\"Google.Protobuf\" />\n    <PackageReference Include=\"Grpc.Core.Api\">\n      <PrivateAssets>all</PrivateAssets>\n    </PackageReference>\n  </ItemGroup>\n</Project>\n";
+        let version: String = String::from("2.63.0");
+
+        assert_eq!(
+            update_package_references(
+                content,
+                &[
+                    (DOTNET_PROTOBUF_PACKAGE, &version),
+                    (DOTNET_GRPC_API_PACKAGE, &version)
+                ]
+            ),
+            content
+        );
+    }
+
+    #[test]
+    fn adds_missing_package_references() {
+        let content: &str = "<Project Sdk=\"Microsoft.NET.Sdk\">\n  <ItemGroup>\n    <PackageReference Include=\"Google.Protobuf\" Version=\"3.27.1\" />\n  </ItemGroup>\n</Project>\n";
+        let protobuf_version: String = String::from("3.27.1");
+        let grpc_version: String = String::from("2.63.0");
+
+        assert_eq!(
+            update_package_references(
+                content,
+                &[
+                    (DOTNET_PROTOBUF_PACKAGE, &protobuf_version),
+                    (DOTNET_GRPC_API_PACKAGE, &grpc_version)
+                ]
+            ),
+            "<Project Sdk=\"Microsoft.NET.Sdk\">\n  <ItemGroup>\n    <PackageReference Include=\"Google.Protobuf\" Version=\"3.27.1\" />\n  </ItemGroup>\n\n  <ItemGroup>\n    <PackageReference Include=\"Grpc.Core.Api\" Version=\"2.63.0\" />\n  </ItemGroup>\n</Project>\n"
+        );
     }
 }
@@ -1,38 +1,115 @@
 //! Go language compiler implementation.
 
-use std::path::PathBuf;
+use std::{cmp::Ordering, path::PathBuf};
+
+use log::debug;
 
 use crate::{
     compilers::{
-        langs_compilers::compiler_types::GoCompiler,
+        langs_compilers::compiler_types::{GoCompiler, OutputKind, ProtocOutput},
         protobuf_compiler::{CompilerProperties, ProtobufCompiler},
+        shared::{compare_versions, grpc_services_options, proto_import_name},
     },
-    parser::types::Project,
+    parser::types::{GoPaths, Project},
 };
 
+/// Go toolchain command.
+static GO_COMMAND: &str = "go";
+
+/// Go protobuf code generator.
+static PROTOC_GEN_GO: &str = "protoc-gen-go";
+
+/// Go gRPC code generator.
+static PROTOC_GEN_GO_GRPC: &str = "protoc-gen-go-grpc";
+
+/// Flag printing the version of the Go toolchain.
+static GO_VERSION_FLAG: &str = "version";
+
+/// Flag printing the version of the Go generators.
+static GO_PLUGIN_VERSION_FLAG: &str = "--version";
+
 /// Required dependencies for Go proto compilation.
 ///
 /// These tools must be installed and available in PATH:
 /// - `protoc`: Protocol Buffers compiler
 /// - `go`: Go toolchain
 /// - `protoc-gen-go`: Go protobuf code generator
-/// - `protoc-gen-go-grpc`: Go gRPC code generator
-static GO_DEPS: [&'static str; 4] = ["protoc", "go", "protoc-gen-go", "protoc-gen-go-grpc"];
+/// - `protoc-gen-go-grpc`: Go gRPC code generator (only when gRPC code is generated)
+static GO_DEPS: [&str; 4] = ["protoc", GO_COMMAND, PROTOC_GEN_GO, PROTOC_GEN_GO_GRPC];
 
 /// Version flags for checking each dependency.
 ///
 /// Each flag corresponds to the dependency at the same index in `GO_DEPS`.
-static GO_VERSION_FLAGS: [&'static str; 4] = ["--version", "version", "--version", "--version"];
+static GO_VERSION_FLAGS: [&str; 4] = [
+    "--version",
+    GO_VERSION_FLAG,
+    GO_PLUGIN_VERSION_FLAG,
+    GO_PLUGIN_VERSION_FLAG,
+];
+
+/// Dependencies left to check when writing `go.mod`, as probing the versions
+/// of the Go tools already checks that they are installed.
+static GO_MOD_DEPS: [&str; 1] = ["protoc"];
+
+/// Version flags for checking each dependency in `GO_MOD_DEPS`.
+static GO_MOD_VERSION_FLAGS: [&str; 1] = ["--version"];
+
+/// Protoc generator for Go message types (`--go_out`).
+static GO_MESSAGES_GENERATOR: &str = "go";
+
+/// Protoc generator for Go gRPC service code (`--go-grpc_out`).
+static GO_GRPC_GENERATOR: &str = "go-grpc";
+
+/// `protoc-gen-go-grpc` options for client-only projects. The plugin always
+/// emits the server interfaces too, but clients don't need them to force
+/// embedding `Unimplemented<Service>Server`.
+static GO_CLIENT_ONLY_OPTIONS: [&str; 1] = ["require_unimplemented_servers=false"];
+
+/// `protoc-gen-go-grpc` options for server-only projects, requiring servers to
+/// embed `Unimplemented<Service>Server` for forward compatibility.
+static GO_SERVER_ONLY_OPTIONS: [&str; 1] = ["require_unimplemented_servers=true"];
 
-/// Protoc flag for generating Go message types.
-static GO_MESSAGES_OUT_ARGUMENT: &'static str = "--go_out";
+/// File option declaring the Go import path (and optionally the package name).
+static GO_PACKAGE_OPTION: &str = "go_package";
 
-/// Protoc flag for generating Go gRPC service code.
-static GO_GRPC_OUT_ARGUMENT: &'static str = "--go-grpc_out";
+/// Separator between the import path and the package name in `go_package`.
+static GO_PACKAGE_NAME_SEPARATOR: char = ';';
+
+/// Generator option stripping the module prefix from output paths.
+static GO_MODULE_OPTION: &str = "module";
+
+/// Generator option placing output files next to their proto files.
+static GO_SOURCE_RELATIVE_OPTION: &str = "paths=source_relative";
+
+/// Prefix of the generator option mapping a proto file to its Go import path.
+static GO_IMPORT_MAPPING_PREFIX: &str = "M";
+
+/// Name of the Go module definition file.
+static GO_MOD_FILENAME: &str = "go.mod";
+
+/// Go module of the runtime used by `protoc-gen-go` output, versioned like the plugin.
+static GO_PROTOBUF_MODULE: &str = "google.golang.org/protobuf";
+
+/// Go module of the runtime used by `protoc-gen-go-grpc` output.
+static GO_GRPC_MODULE: &str = "google.golang.org/grpc";
+
+/// Minimum `google.golang.org/grpc` version required by the code of each
+/// `protoc-gen-go-grpc` 1.x minor version (and the ones after it), newest first.
+static GO_GRPC_REQUIREMENTS: [(u32, &str); 3] = [(5, "v1.64.0"), (4, "v1.62.0"), (0, "v1.32.0")];
+
+/// Versions printed by the Go tools, used to write `go.mod`.
+struct GoToolVersions {
+    /// Version of the Go toolchain (e.g., `1.22.1`).
+    go: String,
+    /// Version of `protoc-gen-go`.
+    protoc_gen_go: String,
+    /// Version of `protoc-gen-go-grpc`, only probed when gRPC code is generated.
+    protoc_gen_go_grpc: Option<String>,
+}
 
 impl CompilerProperties for GoCompiler {
-    fn os_manager(&self) -> &Box<dyn crate::os::types::OSManager> {
-        &self.params.os_manager
+    fn os_manager(&self) -> &dyn crate::os::types::OSManager {
+        self.params.os_manager.as_ref()
     }
 
     fn input_file_path(&self) -> &PathBuf {
@@ -57,16 +134,399 @@ impl ProtobufCompiler for GoCompiler {
     ///
     /// # Generated Output
     ///
-    /// Generates `.pb.go` files for message types and `_grpc.pb.go` files
-    /// for gRPC services in the specified `compiled_proto_folder`.
+    /// Generates `.pb.go` files for message types in `messages_out` and
+    /// `_grpc.pb.go` files for gRPC services in `grpc_out` (both defaulting to
+    /// `compiled_proto_folder`). gRPC code is skipped when `messages_only` is set.
+    ///
+    /// With `go_options.paths: Import` (default), files are placed by their
+    /// `go_package`; setting `go_options.module` strips the module from those
+    /// paths so they stay inside the output folders. With `SourceRelative`,
+    /// files mirror the proto files' folders.
     fn compile_project(&self, project: &Project) -> Result<(), String> {
-        self.assemble_compilation(
-            project,
-            GO_DEPS.to_vec(),
-            GO_VERSION_FLAGS.to_vec(),
-            GO_MESSAGES_OUT_ARGUMENT,
-            GO_GRPC_OUT_ARGUMENT,
-            Option::None,
-        )
+        let go_options: Vec<String> = self.go_generator_options(project)?;
+
+        let mut outputs: Vec<ProtocOutput> = vec![ProtocOutput {
+            generator: String::from(GO_MESSAGES_GENERATOR),
+            options: go_options.clone(),
+            kind: OutputKind::Messages,
+            ..Default::default()
+        }];
+
+        let generates_grpc: bool = project.generates_grpc();
+        if generates_grpc {
+            // protoc-gen-go-grpc always generates both clients and servers, so
+            // grpc_services only controls the server interface requirements
+            let mut grpc_options: Vec<String> = grpc_services_options(
+                project,
+                GO_GRPC_GENERATOR,
+                Some(&GO_CLIENT_ONLY_OPTIONS),
+                Some(&GO_SERVER_ONLY_OPTIONS),
+            );
+            grpc_options.extend(go_options);
+
+            outputs.push(ProtocOutput {
+                generator: String::from(GO_GRPC_GENERATOR),
+                options: grpc_options,
+                kind: OutputKind::Grpc,
+                ..Default::default()
+            });
+        }
+
+        let versions: Option<GoToolVersions> = match project.go_options.go_mod {
+            true => Some(self.probe_tool_versions(generates_grpc)?),
+            false => None,
+        };
+        let (deps, version_flags): (Vec<&'static str>, Vec<&'static str>) = match versions {
+            Some(_) => (GO_MOD_DEPS.to_vec(), GO_MOD_VERSION_FLAGS.to_vec()),
+            None => GO_DEPS
+                .iter()
+                .zip(GO_VERSION_FLAGS.iter())
+                .filter(|(dep, _)| generates_grpc || **dep != PROTOC_GEN_GO_GRPC)
+                .unzip(),
+        };
+
+        self.assemble_compilation(project, deps, version_flags, outputs)?;
+
+        if let Some(versions) = versions {
+            self.write_go_mod(project, &versions)?;
+        }
+
+        Ok(())
+    }
+}
+
+impl GoCompiler {
+    /// Builds the options shared by `protoc-gen-go` and `protoc-gen-go-grpc`.
+    ///
+    /// Checks the `go_package` of every proto file against `go_options.module`,
+    /// and maps the files that don't declare one to an import path inside the
+    /// module (`M<file>=<module>/<folder of the file>`).
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if:
+    /// - A proto file can't be parsed
+    /// - A `go_package` is outside `go_options.module`, so its files would be
+    ///   generated outside the output folders
+    /// - A proto file has no `go_package` and no `go_options.module` is set
+    fn go_generator_options(&self, project: &Project) -> Result<Vec<String>, String> {
+        let module: Option<&String> = project.go_options.module.as_ref();
+        let mut options: Vec<String> = match (project.go_options.paths, module) {
+            (GoPaths::SourceRelative, _) => vec![String::from(GO_SOURCE_RELATIVE_OPTION)],
+            (GoPaths::Import, Some(module)) => vec![format!("{}={}", GO_MODULE_OPTION, module)],
+            (GoPaths::Import, None) => Vec::new(),
+        };
+
+        let include_roots: Vec<PathBuf> = project.include_roots();
+        for proto in project.associated_proto_files.iter() {
+            let go_package: Option<String> = self.get_option(proto, GO_PACKAGE_OPTION)?;
+
+            match (go_package, module) {
+                (Some(go_package), Some(module)) => {
+                    let import_path: &str = go_package
+                        .split(GO_PACKAGE_NAME_SEPARATOR)
+                        .next()
+                        .unwrap_or_default();
+                    if import_path != module && !import_path.starts_with(&format!("{}/", module)) {
+                        return Err(format!(
+                            "The go_package {} of {} is outside the Go module {}, so its files would be generated outside {}",
+                            go_package,
+                            proto,
+                            module,
+                            project.messages_folder()
+                        ));
+                    }
+                }
+                (Some(_), None) => {}
+                (None, Some(module)) => {
+                    let proto_name: PathBuf = proto_import_name(proto, &include_roots);
+                    let folder: String = proto_name
+                        .parent()
+                        .map(|parent| parent.to_string_lossy().to_string())
+                        .unwrap_or_default();
+                    let import_path: String = match folder.is_empty() {
+                        true => module.clone(),
+                        false => format!("{}/{}", module, folder),
+                    };
+
+                    debug!("Mapping {} to the Go import path {}", proto, import_path);
+                    options.push(format!(
+                        "{}{}={}",
+                        GO_IMPORT_MAPPING_PREFIX,
+                        proto_name.to_string_lossy(),
+                        import_path
+                    ));
+                }
+                (None, None) => {
+                    return Err(format!(
+                    "{} has no option go_package. Declare it or set go_options.module to infer it",
+                    proto
+                ))
+                }
+            }
+        }
+
+        Ok(options)
+    }
+
+    /// Writes `go.mod` in `compiled_proto_folder`, or updates the existing one.
+    ///
+    /// The `google.golang.org/protobuf` requirement takes the version of the
+    /// installed `protoc-gen-go`, and `google.golang.org/grpc` (only when gRPC
+    /// code is generated) the minimum version required by the installed
+    /// `protoc-gen-go-grpc`. An existing file only gets lower requirements
+    /// raised, keeping its other requirements and its `go` directive, so
+    /// `go mod tidy` results survive new runs.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if `go_options.module` is not set or the file can't be written.
+    fn write_go_mod(&self, project: &Project, versions: &GoToolVersions) -> Result<(), String> {
+        let module: &String = match &project.go_options.module {
+            Some(module) => module,
+            None => return Err(String::from("go_options.go_mod requires go_options.module")),
+        };
+
+        let mut requirements: Vec<(&'static str, String)> =
+            vec![(GO_PROTOBUF_MODULE, format!("v{}", versions.protoc_gen_go))];
+
+        if let Some(grpc_plugin_version) = &versions.protoc_gen_go_grpc {
+            let grpc_version: &'static str = grpc_requirement(grpc_plugin_version);
+            requirements.push((GO_GRPC_MODULE, String::from(grpc_version)));
+        }
+
+        let go_mod_path: PathBuf =
+            PathBuf::from(&project.compiled_proto_folder).join(GO_MOD_FILENAME);
+        let content: String = match self.os_manager().is_file(&go_mod_path) {
+            true => {
+                let existing: String = self.os_manager().read_file(&go_mod_path)?;
+                update_go_mod(&existing, module, &requirements)
+            }
+            false => {
+                let language_version: String = versions
+                    .go
+                    .split('.')
+                    .take(2)
+                    .collect::<Vec<&str>>()
+                    .join(".");
+
+                let mut lines: Vec<String> = vec![
+                    format!("module {}", module),
+                    String::new(),
+                    format!("go {}", language_version),
+                    String::new(),
+                    String::from("require ("),
+                ];
+                requirements.sort();
+                lines.extend(
+                    requirements
+                        .iter()
+                        .map(|(path, version)| format!("\t{} {}", path, version)),
+                );
+                lines.push(String::from(")"));
+                lines.join("\n") + "\n"
+            }
+        };
+
+        debug!("Writing {}", go_mod_path.display());
+        self.os_manager().write_new_file(&go_mod_path, content)
+    }
+
+    /// Probes the versions of the Go tools, which also checks that they are
+    /// installed. `protoc-gen-go-grpc` is only probed with `generates_grpc`.
+    fn probe_tool_versions(&self, generates_grpc: bool) -> Result<GoToolVersions, String> {
+        let protoc_gen_go_grpc: Option<String> = match generates_grpc {
+            true => Some(self.probe_version(PROTOC_GEN_GO_GRPC, GO_PLUGIN_VERSION_FLAG)?),
+            false => None,
+        };
+
+        Ok(GoToolVersions {
+            go: self.probe_version(GO_COMMAND, GO_VERSION_FLAG)?,
+            protoc_gen_go: self.probe_version(PROTOC_GEN_GO, GO_PLUGIN_VERSION_FLAG)?,
+            protoc_gen_go_grpc,
+        })
+    }
+
+    /// Runs a Go tool with its version flag and extracts the version it prints.
+    fn probe_version(&self, command: &str, flag: &str) -> Result<String, String> {
+        let output: String = self
+            .os_manager()
+            .command_output(command, &[String::from(flag)])?;
+
+        match printed_version(&output) {
+            Some(version) => Ok(version),
+            None => Err(format!(
+                "Can't find the version of {} in {:?}",
+                command, output
+            )),
+        }
+    }
+}
+
+/// Finds the minimum `google.golang.org/grpc` version required by the code of
+/// a `protoc-gen-go-grpc` version (e.g., `v1.64.0` for `1.5.1`).
+fn grpc_requirement(plugin_version: &str) -> &'static str {
+    let minor: u32 = plugin_version
+        .split('.')
+        .nth(1)
+        .and_then(|minor| minor.parse().ok())
+        .unwrap_or_default();
+
+    GO_GRPC_REQUIREMENTS
+        .iter()
+        .find(|(plugin_minor, _)| minor >= *plugin_minor)
+        .map(|(_, version)| *version)
+        .unwrap_or(GO_GRPC_REQUIREMENTS[GO_GRPC_REQUIREMENTS.len() - 1].1)
+}
+
+/// Sets the module path of an existing `go.mod` and raises the given
+/// requirements to at least their version, keeping everything else (newer
+/// versions, e.g., from `go mod tidy`, and comments). Missing requirements
+/// are appended.
+fn update_go_mod(
+    content: &str,
+    module: &String,
+    requirements: &[(&'static str, String)],
+) -> String {
+    let mut pending: Vec<&(&'static str, String)> = requirements.iter().collect();
+    let mut lines: Vec<String> = Vec::new();
+
+    for line in content.lines() {
+        let trimmed: &str = line.trim();
+        if trimmed.starts_with("module ") {
+            lines.push(format!("module {}", module));
+            continue;
+        }
+
+        // Requirements appear as `require path version` or as `path version` in a block
+        let mut tokens = trimmed.trim_start_matches("require ").split_whitespace();
+        let (first_token, current_version) = (tokens.next(), tokens.next());
+
+        match pending
+            .iter()
+            .position(|(path, _)| Some(*path) == first_token)
+        {
+            Some(index) => {
+                let (_, version) = pending.remove(index);
+                // Only the version is replaced, keeping comments such as `// indirect`
+                lines.push(match current_version {
+                    Some(current) if compare_versions(current, version) == Ordering::Less => {
+                        line.replacen(current, version, 1)
+                    }
+                    _ => line.to_string(),
+                });
+            }
+            None => lines.push(line.to_string()),
+        }
+    }
+
+    for (path, version) in pending {
+        lines.push(String::new());
+        lines.push(format!("require {} {}", path, version));
+    }
+
+    lines.join("\n") + "\n"
+}
+
+/// Extracts the first version number printed by a `--version` probe
+/// (e.g., `1.22.1` from `go version go1.22.1 linux/amd64`).
+fn printed_version(output: &str) -> Option<String> {
+    output
+        .split_whitespace()
+        .map(|token| token.trim_start_matches("go").trim_start_matches('v'))
+        .find(|token| token.starts_with(|c: char| c.is_ascii_digit()))
+        .map(String::from)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn requirements() -> Vec<(&'static str, String)> {
+        vec![
+            (GO_PROTOBUF_MODULE, String::from("v1.34.2")),
+            (GO_GRPC_MODULE, String::from("v1.64.0")),
+        ]
+    }
+
+    #[test]
+    fn updates_requirements_inside_blocks() {
+        let content: &str = "module old/module\n\ngo 1.21\n\nrequire (\n\tgithub.com/acme/lib v0.1.0\n\tgoogle.golang.org/grpc v1.50.0 // indirect\n\tgoogle.golang.org/protobuf v1.30.0\n)\n";
+
+        assert_eq!(
+            update_go_mod(content, &String::from("acme.com/protos"), &requirements()),
+            "module acme.com/protos\n\ngo 1.21\n\nrequire (\n\tgithub.com/acme/lib v0.1.0\n\tgoogle.golang.org/grpc v1.64.0 // indirect\n\tgoogle.golang.org/protobuf v1.34.2\n)\n"
+        );
+    }
+
+    #[test]
+    fn updates_single_line_requirements() {
+        let content: &str =
+            "module acme.com/protos\n\ngo 1.22\n\nrequire google.golang.org/protobuf v1.30.0\n";
+
+        assert_eq!(
+            update_go_mod(
+                content,
+                &String::from("acme.com/protos"),
+                &requirements()[..1]
+            ),
+            "module acme.com/protos\n\ngo 1.22\n\nrequire google.golang.org/protobuf v1.34.2\n"
+        );
+    }
+
+    #[test]
+    fn appends_missing_requirements() {
+        let content: &str = "module acme.com/protos\n\ngo 1.22\n";
+
+        assert_eq!(
+            update_go_mod(content, &String::from("acme.com/protos"), &requirements()),
+            "module acme.com/protos\n\ngo 1.22\n\nrequire google.golang.org/protobuf v1.34.2\n\nrequire google.golang.org/grpc v1.64.0\n"
+        );
+    }
+
+    #[test]
+    fn keeps_newer_requirements() {
+        let content: &str = "module acme.com/protos\n\ngo 1.22\n\nrequire (\n\tgoogle.golang.org/grpc v1.66.2\n\tgoogle.golang.org/protobuf v1.35.1\n)\n";
+
+        assert_eq!(
+            update_go_mod(content, &String::from("acme.com/protos"), &requirements()),
+            content
+        );
+    }
+
+    #[test]
+    fn keeps_comments_of_raised_requirements() {
+        let content: &str = "module acme.com/protos\n\ngo 1.22\n\nrequire google.golang.org/grpc v1.50.0 // indirect\n";
+
+        assert_eq!(
+            update_go_mod(content, &String::from("acme.com/protos"), &requirements()),
+            "module acme.com/protos\n\ngo 1.22\n\nrequire google.golang.org/grpc v1.64.0 // indirect\n\nrequire google.golang.org/protobuf v1.34.2\n"
+        );
+    }
+
+    #[test]
+    fn finds_printed_versions() {
+        assert_eq!(
+            printed_version("go version go1.22.1 linux/amd64").as_deref(),
+            Some("1.22.1")
+        );
+        assert_eq!(
+            printed_version("protoc-gen-go v1.34.2").as_deref(),
+            Some("1.34.2")
+        );
+        assert_eq!(
+            printed_version("protoc-gen-go-grpc 1.5.1").as_deref(),
+            Some("1.5.1")
+        );
+        assert_eq!(printed_version("unknown"), None);
+    }
+
+    #[test]
+    fn maps_grpc_plugin_versions_to_grpc_requirements() {
+        assert_eq!(grpc_requirement("1.5.1"), "v1.64.0");
+        assert_eq!(grpc_requirement("1.6.0"), "v1.64.0");
+        assert_eq!(grpc_requirement("1.4.0"), "v1.62.0");
+        assert_eq!(grpc_requirement("1.3.0"), "v1.32.0");
+        assert_eq!(grpc_requirement("unknown"), "v1.32.0");
     }
 }
@@ -0,0 +1,410 @@
+//! Java language compiler implementation.
+//!
+//! This module also holds the helpers shared with the Kotlin compiler, since
+//! both generate the same Java classes and gRPC stubs.
+
+use std::path::{Path, PathBuf};
+
+use log::debug;
+
+use crate::{
+    compilers::{
+        langs_compilers::compiler_types::{JavaCompiler, OutputKind, ProtocOutput},
+        protobuf_compiler::{CompilerProperties, ProtobufCompiler},
+        shared::grpc_services_options,
+    },
+    parser::types::{JvmLayout, Project},
+};
+
+impl CompilerProperties for JavaCompiler {
+    fn os_manager(&self) -> &dyn crate::os::types::OSManager {
+        self.params.os_manager.as_ref()
+    }
+
+    fn input_file_path(&self) -> &PathBuf {
+        &self.params.input_file_path
+    }
+}
+
+/// Required dependencies for Java and Kotlin proto compilation.
+///
+/// These tools must be installed and available:
+/// - `protoc`: Protocol Buffers compiler
+///
+/// No Java runtime is needed, since `protoc-gen-grpc-java` is a native binary.
+/// It is looked up separately, as it has no version flag.
+pub(crate) static JVM_DEPS: [&str; 1] = ["protoc"];
+
+/// Version flags for checking each dependency.
+pub(crate) static JVM_VERSION_FLAGS: [&str; 1] = ["--version"];
+
+/// Protoc generator for Java message types (`--java_out`).
+pub(crate) static JAVA_MESSAGES_GENERATOR: &str = "java";
+
+/// Protoc generator for Java gRPC service code (`--grpc-java_out`), backed by
+/// the `protoc-gen-grpc-java` plugin.
+pub(crate) static JAVA_GRPC_GENERATOR: &str = "grpc-java";
+
+/// Executable of the Java gRPC plugin, looked up in PATH without `plugin_path`.
+static JAVA_PLUGIN_NAME: &str = "protoc-gen-grpc-java";
+
+/// Subfolder used by each generator in the `protobuf-maven-plugin` layout.
+static MAVEN_LAYOUT: [(&str, &str); 3] = [
+    ("java", "java"),
+    ("grpc-java", "grpc-java"),
+    ("kotlin", "kotlin"),
+];
+
+/// Subfolder used by each generator in the `protobuf-gradle-plugin` layout.
+static GRADLE_LAYOUT: [(&str, &str); 3] = [
+    ("java", "java"),
+    ("grpc-java", "grpc"),
+    ("kotlin", "kotlin"),
+];
+
+/// Option overriding the Java package of the generated classes.
+static JAVA_PACKAGE_OPTION: &str = "java_package";
+
+/// Option generating one Java file per top-level type.
+static JAVA_MULTIPLE_FILES_OPTION: &str = "java_multiple_files";
+
+/// Option overriding the name of the Java outer class.
+static JAVA_OUTER_CLASSNAME_OPTION: &str = "java_outer_classname";
+
+/// Suffix protoc appends to the outer class when it clashes with a declared type.
+static JAVA_OUTER_CLASS_SUFFIX: &str = "OuterClass";
+
+impl ProtobufCompiler for JavaCompiler {
+    /// Compiles proto files to Java code.
+    ///
+    /// This implementation uses protoc's built-in `--java_out` for message types
+    /// and the `protoc-gen-grpc-java` plugin for gRPC services. The plugin is taken
+    /// from the project's `plugin_path` field or, if absent, from PATH.
+    ///
+    /// # Arguments
+    ///
+    /// * `project` - Project configuration with Java-specific settings
+    ///
+    /// # Returns
+    ///
+    /// * `Ok(())` - Java code generated successfully
+    /// * `Err(String)` - Error message if compilation fails
+    ///
+    /// # Generated Output
+    ///
+    /// Generates `.java` files in folders derived from `option java_package`
+    /// (or the proto package), inside the folders given by `jvm_options.layout`
+    /// under `messages_out` and `grpc_out`. gRPC stubs are skipped when
+    /// `messages_only` is set.
+    fn compile_project(&self, project: &Project) -> Result<(), String> {
+        let mut outputs: Vec<ProtocOutput> = vec![jvm_output(
+            project,
+            JAVA_MESSAGES_GENERATOR,
+            OutputKind::Messages,
+            None,
+        )];
+
+        if project.generates_grpc() {
+            outputs.push(grpc_java_output(self, project)?);
+        }
+
+        let java_folder = PathBuf::from(outputs[0].folder(project));
+        let grpc_folder: Option<PathBuf> = java_grpc_folder(project, &outputs);
+
+        self.assemble_compilation(
+            project,
+            JVM_DEPS.to_vec(),
+            JVM_VERSION_FLAGS.to_vec(),
+            outputs,
+        )?;
+
+        verify_java_layout(self, project, &java_folder, grpc_folder.as_deref())
+    }
+}
+
+/// Builds the protoc output of a JVM generator, placing it in the subfolder
+/// required by the project's `jvm_options.layout` inside the folder of its kind.
+pub(crate) fn jvm_output(
+    project: &Project,
+    generator: &'static str,
+    kind: OutputKind,
+    plugin_path: Option<String>,
+) -> ProtocOutput {
+    let layout: &[(&'static str, &'static str)] = match project.jvm_options.layout {
+        JvmLayout::Flat => &[],
+        JvmLayout::Maven => &MAVEN_LAYOUT,
+        JvmLayout::Gradle => &GRADLE_LAYOUT,
+    };
+
+    // protoc-gen-grpc-java always generates both stubs and service bases
+    let options: Vec<String> = match kind {
+        OutputKind::Grpc => grpc_services_options(project, generator, None, None),
+        _ => Vec::new(),
+    };
+
+    let mut output = ProtocOutput {
+        generator: String::from(generator),
+        options,
+        plugin_path,
+        kind,
+        ..Default::default()
+    };
+
+    output.out_folder = layout
+        .iter()
+        .find(|(layout_generator, _)| *layout_generator == generator)
+        .map(|(_, subfolder)| {
+            PathBuf::from(output.folder(project))
+                .join(subfolder)
+                .to_string_lossy()
+                .to_string()
+        });
+
+    output
+}
+
+/// Builds the `grpc-java` output, taking `protoc-gen-grpc-java` from the
+/// project's `plugin_path` or, if absent, from PATH.
+///
+/// # Errors
+///
+/// Returns an error if there is no `plugin_path` and the plugin is not in PATH.
+pub(crate) fn grpc_java_output<C: ProtobufCompiler + ?Sized>(
+    compiler: &C,
+    project: &Project,
+) -> Result<ProtocOutput, String> {
+    let plugin_path: String = match &project.plugin_path {
+        Some(plugin_path) => compiler.plugin_executable(plugin_path)?,
+        None => match compiler.os_manager().find_executable(JAVA_PLUGIN_NAME) {
+            Some(plugin_path) => plugin_path.to_string_lossy().to_string(),
+            None => {
+                return Err(format!(
+                    "The plugin {} must have a path in plugin_path option or be in PATH",
+                    JAVA_PLUGIN_NAME
+                ))
+            }
+        },
+    };
+
+    Ok(jvm_output(
+        project,
+        JAVA_GRPC_GENERATOR,
+        OutputKind::Grpc,
+        Some(plugin_path),
+    ))
+}
+
+/// Returns the folder of the `grpc-java` output, if gRPC stubs are generated.
+pub(crate) fn java_grpc_folder(project: &Project, outputs: &[ProtocOutput]) -> Option<PathBuf> {
+    outputs
+        .iter()
+        .find(|output| output.generator == JAVA_GRPC_GENERATOR)
+        .map(|output| PathBuf::from(output.folder(project)))
+}
+
+/// Checks that protoc laid out the Java sources of every proto file the way
+/// its options ask for.
+///
+/// protoc derives the folder from `java_package` (falling back to the proto
+/// package) and the outer class from `java_outer_classname` (falling back to
+/// the file name in CamelCase). With `java_multiple_files`, each top-level
+/// message (along with its `OrBuilder` interface) and enum gets its own file
+/// next to the outer class. When `grpc_folder` is given, each service must
+/// have its `<Service>Grpc.java` stub in the same package folder there.
+///
+/// # Errors
+///
+/// Returns an error listing the missing files, which usually means the output
+/// landed outside the expected source root.
+pub(crate) fn verify_java_layout<C: ProtobufCompiler + ?Sized>(
+    compiler: &C,
+    project: &Project,
+    java_folder: &Path,
+    grpc_folder: Option<&Path>,
+) -> Result<(), String> {
+    let mut missing: Vec<String> = Vec::new();
+
+    for proto in project.associated_proto_files.iter() {
+        let file = compiler.parse_proto(proto)?;
+        let java_package = match file.option(JAVA_PACKAGE_OPTION) {
+            Some(java_package) => java_package.clone(),
+            None => file.package.clone().unwrap_or_default(),
+        };
+        let package_path: PathBuf = java_package
+            .split('.')
+            .filter(|part| !part.is_empty())
+            .collect();
+
+        let outer_class = match file.option(JAVA_OUTER_CLASSNAME_OPTION) {
+            Some(outer_class) => outer_class.clone(),
+            None => outer_class_name(proto),
+        };
+
+        let package_folder: PathBuf = java_folder.join(&package_path);
+        let outer_class_found = [
+            format!("{}.java", outer_class),
+            format!("{}{}.java", outer_class, JAVA_OUTER_CLASS_SUFFIX),
+        ]
+        .iter()
+        .any(|filename| {
+            compiler
+                .os_manager()
+                .is_file(&package_folder.join(filename))
+        });
+        if !outer_class_found {
+            missing.push(
+                package_folder
+                    .join(format!("{}.java", outer_class))
+                    .display()
+                    .to_string(),
+            );
+        }
+
+        let mut expected: Vec<PathBuf> = Vec::new();
+        if file.option(JAVA_MULTIPLE_FILES_OPTION).map(String::as_str) == Some("true") {
+            for message in file.messages.iter() {
+                expected.push(package_folder.join(format!("{}.java", message.name)));
+                expected.push(package_folder.join(format!("{}OrBuilder.java", message.name)));
+            }
+            for enumeration in file.enums.iter() {
+                expected.push(package_folder.join(format!("{}.java", enumeration.name)));
+            }
+        }
+        if let Some(grpc_folder) = grpc_folder {
+            for service in file.services.iter() {
+                expected.push(
+                    grpc_folder
+                        .join(&package_path)
+                        .join(format!("{}Grpc.java", service.name)),
+                );
+            }
+        }
+
+        for file in expected {
+            match compiler.os_manager().is_file(&file) {
+                true => debug!("Java source {} of {} generated", file.display(), proto),
+                false => missing.push(file.display().to_string()),
+            }
+        }
+    }
+
+    match missing.is_empty() {
+        true => Ok(()),
+        false => Err(format!(
+            "protoc did not generate the following Java sources where java_package and java_multiple_files place them: {}",
+            missing.join(", ")
+        )),
+    }
+}
+
+/// Computes the default Java outer class name of a proto file, following
+/// protoc's rules: the file name is converted to CamelCase, treating every
+/// non-alphanumeric character as a word separator.
+fn outer_class_name(proto: &String) -> String {
+    let stem = PathBuf::from(proto)
+        .file_stem()
+        .unwrap_or_default()
+        .to_string_lossy()
+        .to_string();
+
+    let mut result = String::new();
+    let mut capitalize_next = true;
+    for character in stem.chars() {
+        if character.is_ascii_alphabetic() {
+            match capitalize_next {
+                true => result.push(character.to_ascii_uppercase()),
+                false => result.push(character),
+            }
+            capitalize_next = false;
+        } else if character.is_ascii_digit() {
+            result.push(character);
+            capitalize_next = true;
+        } else {
+            capitalize_next = true;
+        }
+    }
+
+    result
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        compilers::langs_compilers::compiler_types::CompilerParams, os::types::UnixManager,
+        parser::types::Lang,
+    };
+    use std::fs;
+
+    #[test]
+    fn names_outer_classes_like_protoc() {
+        assert_eq!(
+            outer_class_name(&String::from("protos/users.proto")),
+            "Users"
+        );
+        assert_eq!(
+            outer_class_name(&String::from("user_roles.proto")),
+            "UserRoles"
+        );
+        assert_eq!(
+            outer_class_name(&String::from("api-v2beta.proto")),
+            "ApiV2Beta"
+        );
+        assert_eq!(
+            outer_class_name(&String::from("userRoles.proto")),
+            "UserRoles"
+        );
+    }
+
+    #[test]
+    fn verifies_java_sources_and_grpc_stubs() {
+        let root: PathBuf =
+            std::env::temp_dir().join(format!("protoweld-java-layout-{}", std::process::id()));
+        let proto: PathBuf = root.join("users.proto");
+        fs::create_dir_all(&root).unwrap();
+        fs::write(
+            &proto,
+            "syntax = \"proto3\";\npackage acme.users;\noption java_package = \"com.acme.users\";\noption java_multiple_files = true;\nmessage User {}\nenum Role { ROLE_UNSPECIFIED = 0; }\nservice Users {}\n",
+        )
+        .unwrap();
+
+        let java_folder: PathBuf = root.join("java");
+        let grpc_folder: PathBuf = root.join("grpc");
+        let package_folder: PathBuf = java_folder.join("com/acme/users");
+        fs::create_dir_all(&package_folder).unwrap();
+        fs::create_dir_all(grpc_folder.join("com/acme/users")).unwrap();
+        for file in [
+            package_folder.join("UsersOuterClass.java"),
+            package_folder.join("User.java"),
+            package_folder.join("UserOrBuilder.java"),
+            package_folder.join("Role.java"),
+            grpc_folder.join("com/acme/users/UsersGrpc.java"),
+        ] {
+            fs::write(file, "").unwrap();
+        }
+
+        let project: Project = serde_yaml::from_str(&format!(
+            "path: java\nlang: Java\ncompiled_proto_folder: {}\nassociated_proto_files: [{}]\n",
+            root.display(),
+            proto.display()
+        ))
+        .unwrap();
+        let compiler = JavaCompiler {
+            params: CompilerParams {
+                os_manager: Box::new(UnixManager),
+                input_file_path: PathBuf::from("protoweld.yaml"),
+                lang: Lang::Java,
+            },
+        };
+
+        let complete = verify_java_layout(&compiler, &project, &java_folder, Some(&grpc_folder));
+        fs::remove_file(package_folder.join("Role.java")).unwrap();
+        let incomplete = verify_java_layout(&compiler, &project, &java_folder, Some(&grpc_folder));
+        fs::remove_dir_all(&root).unwrap();
+
+        assert!(complete.is_ok());
+        let error: String = incomplete.unwrap_err();
+        assert!(error.contains("Role.java"));
+        assert!(!error.contains("User.java"));
+    }
+}
@@ -0,0 +1,81 @@
+//! Kotlin language compiler implementation.
+
+use std::path::PathBuf;
+
+use crate::{
+    compilers::{
+        langs_compilers::{
+            compiler_types::{KotlinCompiler, OutputKind, ProtocOutput},
+            java_compiler::{
+                grpc_java_output, java_grpc_folder, jvm_output, verify_java_layout,
+                JAVA_MESSAGES_GENERATOR, JVM_DEPS, JVM_VERSION_FLAGS,
+            },
+        },
+        protobuf_compiler::{CompilerProperties, ProtobufCompiler},
+    },
+    parser::types::Project,
+};
+
+impl CompilerProperties for KotlinCompiler {
+    fn os_manager(&self) -> &dyn crate::os::types::OSManager {
+        self.params.os_manager.as_ref()
+    }
+
+    fn input_file_path(&self) -> &PathBuf {
+        &self.params.input_file_path
+    }
+}
+
+/// Protoc generator for Kotlin DSL builders and extensions (`--kotlin_out`).
+static KOTLIN_MESSAGES_GENERATOR: &str = "kotlin";
+
+impl ProtobufCompiler for KotlinCompiler {
+    /// Compiles proto files to Kotlin code.
+    ///
+    /// protoc's `--kotlin_out` only generates DSL wrappers around the Java
+    /// message classes, so this implementation also runs `--java_out` and the
+    /// `protoc-gen-grpc-java` plugin (from `plugin_path` or PATH).
+    ///
+    /// # Arguments
+    ///
+    /// * `project` - Project configuration with Kotlin-specific settings
+    ///
+    /// # Returns
+    ///
+    /// * `Ok(())` - Kotlin code generated successfully
+    /// * `Err(String)` - Error message if compilation fails
+    ///
+    /// # Generated Output
+    ///
+    /// Generates `.java` and `.kt` files in folders derived from
+    /// `option java_package`, inside the folders given by `jvm_options.layout`
+    /// under `messages_out` and `grpc_out`. gRPC stubs are skipped when
+    /// `messages_only` is set.
+    fn compile_project(&self, project: &Project) -> Result<(), String> {
+        let mut outputs: Vec<ProtocOutput> = vec![
+            jvm_output(project, JAVA_MESSAGES_GENERATOR, OutputKind::Messages, None),
+            jvm_output(
+                project,
+                KOTLIN_MESSAGES_GENERATOR,
+                OutputKind::Messages,
+                None,
+            ),
+        ];
+
+        if project.generates_grpc() {
+            outputs.push(grpc_java_output(self, project)?);
+        }
+
+        let java_folder = PathBuf::from(outputs[0].folder(project));
+        let grpc_folder: Option<PathBuf> = java_grpc_folder(project, &outputs);
+
+        self.assemble_compilation(
+            project,
+            JVM_DEPS.to_vec(),
+            JVM_VERSION_FLAGS.to_vec(),
+            outputs,
+        )?;
+
+        verify_java_layout(self, project, &java_folder, grpc_folder.as_deref())
+    }
+}
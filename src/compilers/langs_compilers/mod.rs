@@ -7,4 +7,10 @@
 pub mod go_compiler;
 pub mod dotnet_compiler;
 pub mod rust_compiler;
+pub mod python_compiler;
+pub mod typescript_compiler;
+pub mod java_compiler;
+pub mod kotlin_compiler;
+pub mod cpp_compiler;
+pub mod custom_compiler;
 pub mod compiler_types;
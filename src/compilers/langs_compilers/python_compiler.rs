@@ -0,0 +1,426 @@
+//! Python language compiler implementation with post-processing.
+//!
+//! This compiler generates Python code using `grpcio-tools`, which bundles
+//! `protoc` together with the gRPC Python plugin. It includes post-processing
+//! to make the output folder importable as a regular Python package.
+
+use std::{
+    collections::{BTreeSet, HashSet},
+    path::{Path, PathBuf},
+};
+
+use log::debug;
+
+use crate::{
+    compilers::{
+        langs_compilers::compiler_types::{OutputKind, ProtocOutput, PythonCompiler},
+        protobuf_compiler::{CompilerProperties, ProtobufCompiler},
+        shared::grpc_services_options,
+    },
+    parser::types::Project,
+};
+
+impl CompilerProperties for PythonCompiler {
+    fn os_manager(&self) -> &dyn crate::os::types::OSManager {
+        self.params.os_manager.as_ref()
+    }
+
+    fn input_file_path(&self) -> &PathBuf {
+        &self.params.input_file_path
+    }
+}
+
+/// Command used to invoke the protoc bundled with `grpcio-tools`.
+static PYTHON_PROTOC_COMMAND: [&str; 3] = ["python3", "-m", "grpc_tools.protoc"];
+
+/// Name of the Python package providing `grpc_tools.protoc` (used for error messages).
+///
+/// Probing `python3 -m grpc_tools.protoc` checks both the interpreter and the
+/// package, so no other dependency is checked.
+static PYTHON_GRPC_TOOLS_PACKAGE: &str = "grpcio-tools";
+
+/// Protoc generator for Python message types (`--python_out`).
+static PYTHON_MESSAGES_GENERATOR: &str = "python";
+
+/// Protoc generator for Python gRPC service code (`--grpc_python_out`).
+static PYTHON_GRPC_GENERATOR: &str = "grpc_python";
+
+/// Protoc generator for `.pyi` typed stubs (`--pyi_out`).
+static PYTHON_STUBS_GENERATOR: &str = "pyi";
+
+/// Suffixes of the files produced by `grpc_tools.protoc`.
+static PYTHON_GENERATED_SUFFIXES: [&str; 3] = ["_pb2.py", "_pb2_grpc.py", "_pb2.pyi"];
+
+/// Standard Python package marker filename.
+static PYTHON_PACKAGE_FILENAME: &str = "__init__.py";
+
+impl ProtobufCompiler for PythonCompiler {
+    /// Compiles proto files to Python code with post-processing.
+    ///
+    /// This implementation:
+    /// 1. Checks that `python3` can import `grpcio-tools`, its only dependency
+    /// 2. Compiles proto files using `python3 -m grpc_tools.protoc`
+    /// 3. Rewrites absolute imports between generated modules to package-relative form
+    /// 4. Creates an `__init__.py` file for each generated package
+    ///
+    /// # Arguments
+    ///
+    /// * `project` - Project configuration with Python-specific settings
+    ///
+    /// # Returns
+    ///
+    /// * `Ok(())` - Python code generated and organized successfully
+    /// * `Err(String)` - Error message if compilation or post-processing fails
+    ///
+    /// # Generated Output
+    ///
+    /// Generates `_pb2.py` files for message types and, when
+    /// `python_options.typed_stubs` is enabled, `_pb2.pyi` stubs in `messages_out`,
+    /// plus `_pb2_grpc.py` files for gRPC services in `grpc_out` (both defaulting
+    /// to `compiled_proto_folder`). gRPC code is skipped when `messages_only` is set.
+    ///
+    /// # Post-Processing Details
+    ///
+    /// `grpc_tools.protoc` emits imports such as `from protos.security import auth_pb2`
+    /// which only work when the output folder itself is on `sys.path`. Imports that
+    /// point to another generated module are rewritten to `from . import auth_pb2`
+    /// (or `from ..other import ...`), so the output folder works as a package.
+    /// When messages and services are generated in different folders, each one
+    /// is processed as its own package and imports across them are kept absolute.
+    fn compile_project(&self, project: &Project) -> Result<(), String> {
+        // grpc_tools is a Python module, so it can't be checked as a plain command
+        if self
+            .os_manager()
+            .execute_command(
+                PYTHON_PROTOC_COMMAND[0],
+                &PYTHON_PROTOC_COMMAND[1..]
+                    .iter()
+                    .map(|arg| arg.to_string())
+                    .chain([String::from("--version")])
+                    .collect::<Vec<String>>(),
+                true,
+                None,
+            )
+            .is_err()
+        {
+            return Err(format!(
+                "Failed to check installation of the following dependencies: {},{}",
+                PYTHON_PROTOC_COMMAND[0], PYTHON_GRPC_TOOLS_PACKAGE
+            ));
+        }
+
+        let mut outputs: Vec<ProtocOutput> = vec![ProtocOutput {
+            generator: String::from(PYTHON_MESSAGES_GENERATOR),
+            kind: OutputKind::Messages,
+            ..Default::default()
+        }];
+
+        if project.generates_grpc() {
+            outputs.push(ProtocOutput {
+                generator: String::from(PYTHON_GRPC_GENERATOR),
+                // The gRPC Python plugin always generates both stubs and servicers
+                options: grpc_services_options(project, PYTHON_GRPC_GENERATOR, None, None),
+                kind: OutputKind::Grpc,
+                ..Default::default()
+            });
+        }
+
+        if project.python_options.typed_stubs {
+            outputs.push(ProtocOutput {
+                generator: String::from(PYTHON_STUBS_GENERATOR),
+                kind: OutputKind::Messages,
+                ..Default::default()
+            });
+        }
+
+        let folders: BTreeSet<PathBuf> = outputs
+            .iter()
+            .map(|output| PathBuf::from(output.folder(project)))
+            .collect();
+
+        self.assemble_compilation(
+            project,
+            Vec::new(),
+            Vec::new(),
+            outputs,
+        )?;
+
+        for folder in folders.iter() {
+            self.organize_package(folder)?;
+        }
+
+        Ok(())
+    }
+
+    fn protoc_command(&self) -> Vec<&'static str> {
+        PYTHON_PROTOC_COMMAND.to_vec()
+    }
+
+    /// `grpc_tools.protoc` already adds the well-known types bundled with `grpcio-tools`.
+    fn well_known_types_include(&self) -> Option<PathBuf> {
+        None
+    }
+}
+
+impl PythonCompiler {
+    /// Makes an output folder importable as a Python package, rewriting the
+    /// imports between its generated modules and adding missing `__init__.py` files.
+    fn organize_package(&self, base_path: &PathBuf) -> Result<(), String> {
+        let generated_files: Vec<PathBuf> = self
+            .os_manager()
+            .list_files(base_path)?
+            .into_iter()
+            .filter(|file| {
+                let filename = file.file_name().unwrap_or_default().to_string_lossy();
+                PYTHON_GENERATED_SUFFIXES
+                    .iter()
+                    .any(|suffix| filename.ends_with(suffix))
+            })
+            .collect();
+
+        // Dotted names of every generated module, relative to the output folder
+        let modules: HashSet<String> = generated_files
+            .iter()
+            .map(|file| module_parts(base_path, file).join("."))
+            .collect();
+
+        // Rewrite imports between generated modules to package-relative form
+        for file in generated_files.iter() {
+            let mut package = module_parts(base_path, file);
+            package.pop();
+
+            let content = self.os_manager().read_file(file)?;
+            let mut changed = false;
+            let mut lines: Vec<String> = Vec::new();
+
+            for line in content.lines() {
+                match relative_import(line, &package, &modules) {
+                    Some(rewritten) => {
+                        changed = true;
+                        lines.push(rewritten);
+                    }
+                    None => lines.push(line.to_string()),
+                }
+            }
+
+            if changed {
+                debug!("Rewrote generated imports in {}", file.display());
+                let mut new_content = lines.join("\n");
+                if content.ends_with('\n') {
+                    new_content.push('\n');
+                }
+                self.os_manager().write_new_file(file, new_content)?;
+            }
+        }
+
+        // Create an __init__.py for the output folder and every generated subpackage
+        let mut package_dirs: BTreeSet<PathBuf> = BTreeSet::new();
+        for file in generated_files.iter() {
+            let mut dir = file.parent();
+            while let Some(current) = dir {
+                if !current.starts_with(base_path) {
+                    break;
+                }
+                package_dirs.insert(current.to_path_buf());
+                dir = current.parent();
+            }
+        }
+
+        for dir in package_dirs.iter() {
+            let init_file: PathBuf = dir.join(PYTHON_PACKAGE_FILENAME);
+            if !self.os_manager().is_file(&init_file) {
+                self.os_manager()
+                    .write_new_file(&init_file, String::new())?;
+            }
+        }
+
+        Ok(())
+    }
+}
+
+/// Splits a generated file path into its dotted module components.
+///
+/// For example `out/protos/security/auth_pb2.py` with base `out` yields
+/// `["protos", "security", "auth_pb2"]`.
+fn module_parts(base_path: &Path, file: &Path) -> Vec<String> {
+    let relative = file.strip_prefix(base_path).unwrap_or(file);
+    let mut parts: Vec<String> = relative
+        .parent()
+        .map(|parent| {
+            parent
+                .components()
+                .map(|component| component.as_os_str().to_string_lossy().to_string())
+                .collect()
+        })
+        .unwrap_or_default();
+
+    if let Some(stem) = relative.file_stem() {
+        parts.push(stem.to_string_lossy().to_string());
+    }
+
+    parts
+}
+
+/// Rewrites an absolute import of a generated module into a relative one.
+///
+/// Handles the two forms emitted by `grpc_tools.protoc`:
+/// `import auth_pb2 as auth__pb2` and `from protos.security import auth_pb2 as alias`.
+///
+/// # Returns
+///
+/// * `Some(String)` - The rewritten line
+/// * `None` - The line is not an import of a generated module and must be kept
+fn relative_import(line: &str, package: &[String], modules: &HashSet<String>) -> Option<String> {
+    let (target, alias, from_import) = if let Some(rest) = line.strip_prefix("import ") {
+        match rest.split_once(" as ") {
+            Some((target, alias)) => (target.trim().to_string(), Some(alias.trim()), false),
+            None => (rest.trim().to_string(), None, false),
+        }
+    } else if let Some(rest) = line.strip_prefix("from ") {
+        let (from, imported) = rest.split_once(" import ")?;
+        if from.starts_with('.') {
+            return None;
+        }
+
+        match imported.split_once(" as ") {
+            Some((name, alias)) => (
+                format!("{}.{}", from.trim(), name.trim()),
+                Some(alias.trim()),
+                true,
+            ),
+            None => (format!("{}.{}", from.trim(), imported.trim()), None, true),
+        }
+    } else {
+        return None;
+    };
+
+    if !modules.contains(&target) {
+        return None;
+    }
+
+    let parts: Vec<&str> = target.split('.').collect();
+    let (name, target_package) = parts.split_last()?;
+
+    // `import a.b_pb2` is referenced as `a.b_pb2.X`, which a relative import can't keep
+    if !from_import && alias.is_none() && !target_package.is_empty() {
+        return None;
+    }
+
+    let common = package
+        .iter()
+        .zip(target_package.iter())
+        .take_while(|(current, target)| current.as_str() == **target)
+        .count();
+
+    let from = format!(
+        "{}{}",
+        ".".repeat(package.len() - common + 1),
+        target_package[common..].join(".")
+    );
+
+    Some(match alias {
+        Some(alias) => format!("from {} import {} as {}", from, name, alias),
+        None => format!("from {} import {}", from, name),
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn modules(names: &[&str]) -> HashSet<String> {
+        names.iter().map(|name| name.to_string()).collect()
+    }
+
+    fn package(parts: &[&str]) -> Vec<String> {
+        parts.iter().map(|part| part.to_string()).collect()
+    }
+
+    #[test]
+    fn splits_generated_files_into_modules() {
+        assert_eq!(
+            module_parts(
+                Path::new("out"),
+                Path::new("out/protos/security/auth_pb2.py")
+            ),
+            vec!["protos", "security", "auth_pb2"]
+        );
+        assert_eq!(
+            module_parts(Path::new("out"), Path::new("out/auth_pb2_grpc.py")),
+            vec!["auth_pb2_grpc"]
+        );
+    }
+
+    #[test]
+    fn rewrites_same_folder_imports() {
+        let generated: HashSet<String> = modules(&["auth_pb2", "auth_pb2_grpc"]);
+
+        assert_eq!(
+            relative_import("import auth_pb2 as auth__pb2", &[], &generated).as_deref(),
+            Some("from . import auth_pb2 as auth__pb2")
+        );
+        assert_eq!(relative_import("import grpc", &[], &generated), None);
+    }
+
+    #[test]
+    fn rewrites_imports_between_nested_packages() {
+        let generated: HashSet<String> = modules(&[
+            "protos.security.auth_pb2",
+            "protos.security.roles_pb2",
+            "protos.common.money_pb2",
+        ]);
+        let security: Vec<String> = package(&["protos", "security"]);
+
+        assert_eq!(
+            relative_import(
+                "from protos.security import roles_pb2 as protos_dot_security_dot_roles__pb2",
+                &security,
+                &generated
+            )
+            .as_deref(),
+            Some("from . import roles_pb2 as protos_dot_security_dot_roles__pb2")
+        );
+        assert_eq!(
+            relative_import(
+                "from protos.common import money_pb2 as money",
+                &security,
+                &generated
+            )
+            .as_deref(),
+            Some("from ..common import money_pb2 as money")
+        );
+        assert_eq!(
+            relative_import(
+                "from protos.security import auth_pb2",
+                &package(&[]),
+                &generated
+            )
+            .as_deref(),
+            Some("from .protos.security import auth_pb2")
+        );
+        // Dotted references can't be kept by a relative import
+        assert_eq!(
+            relative_import("import protos.common.money_pb2", &security, &generated),
+            None
+        );
+        assert_eq!(
+            relative_import("from . import roles_pb2", &security, &generated),
+            None
+        );
+    }
+
+    #[test]
+    fn keeps_imports_of_modules_in_another_output_folder() {
+        // With split messages_out and grpc_out, the grpc folder only holds the stubs
+        let grpc_modules: HashSet<String> = modules(&["security.auth_pb2_grpc"]);
+
+        assert_eq!(
+            relative_import(
+                "from security import auth_pb2 as security_dot_auth__pb2",
+                &package(&["security"]),
+                &grpc_modules
+            ),
+            None
+        );
+    }
+}
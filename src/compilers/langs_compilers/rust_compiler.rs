@@ -4,18 +4,27 @@
 //! for gRPC services. It includes special post-processing to organize generated
 //! files into proper Rust module structures.
 
-use std::{collections::HashSet, path::PathBuf};
+use std::{
+    collections::{BTreeMap, BTreeSet, HashSet},
+    path::{Path, PathBuf},
+};
 
 use log::debug;
+use prost::Message;
+use prost_types::FileDescriptorSet;
 
-use crate::compilers::{
-    langs_compilers::compiler_types::RustCompiler,
-    protobuf_compiler::{CompilerProperties, ProtobufCompiler},
+use crate::{
+    compilers::{
+        langs_compilers::compiler_types::{OutputKind, ProtocOutput, RustCompiler},
+        protobuf_compiler::{CompilerProperties, ProtobufCompiler},
+        shared::grpc_services_options,
+    },
+    parser::types::{GrpcServices, Project, RustBackend, RustOptions, RustRootModule},
 };
 
 impl CompilerProperties for RustCompiler {
-    fn os_manager(&self) -> &Box<dyn crate::os::types::OSManager> {
-        &self.params.os_manager
+    fn os_manager(&self) -> &dyn crate::os::types::OSManager {
+        self.params.os_manager.as_ref()
     }
 
     fn input_file_path(&self) -> &PathBuf {
@@ -26,46 +35,95 @@ impl CompilerProperties for RustCompiler {
 /// Required dependencies for Rust proto compilation.
 ///
 /// These tools must be installed and available:
-/// - `protoc`: Protocol Buffers compiler
+/// - `protoc`: Protocol Buffers compiler (the only one needed by the library backend)
 /// - `protoc-gen-tonic`: Tonic gRPC code generator
 /// - `protoc-gen-prost`: Prost message type generator
-static RUST_DEPS: [&'static str; 3] = ["protoc", "protoc-gen-tonic", "protoc-gen-prost"];
+static RUST_DEPS: [&str; 3] = ["protoc", "protoc-gen-tonic", "protoc-gen-prost"];
 
 /// Version flags for checking each dependency.
 ///
 /// Note: `protoc-gen-tonic` and `protoc-gen-prost` don't have standard version
 /// flags, so empty strings are used (they'll be checked during actual compilation).
-static RUST_VERSION_FLAGS: [&'static str; 3] = ["--version", "", ""];
+static RUST_VERSION_FLAGS: [&str; 3] = ["--version", "", ""];
+
+/// Tonic options generating client stubs only.
+static RUST_CLIENT_ONLY_OPTIONS: [&str; 1] = ["no_server"];
+
+/// Tonic options generating server traits only.
+static RUST_SERVER_ONLY_OPTIONS: [&str; 1] = ["no_client"];
+
+/// Prost (and Tonic) option mapping a proto path to an existing Rust path.
+static PROST_EXTERN_PATH_OPTION: &str = "extern_path";
+
+/// Prost option adding an attribute to messages and enums.
+static PROST_TYPE_ATTRIBUTE_OPTION: &str = "type_attribute";
+
+/// Prost option adding an attribute to message fields.
+static PROST_FIELD_ATTRIBUTE_OPTION: &str = "field_attribute";
+
+/// Prost option generating `bytes::Bytes` instead of `Vec<u8>`.
+static PROST_BYTES_OPTION: &str = "bytes";
+
+/// Dependency only required when gRPC services are generated.
+static RUST_GRPC_DEP: &str = "protoc-gen-tonic";
 
-/// Protoc flag for generating Prost message types.
-static RUST_MESSAGES_OUT_ARGUMENT: &'static str = "--prost_out";
+/// Protoc generator for Prost message types (`--prost_out`).
+static RUST_MESSAGES_GENERATOR: &str = "prost";
 
-/// Protoc flag for generating Tonic gRPC service code.
-static RUST_GRPC_OUT_ARGUMENT: &'static str = "--tonic_out";
+/// Protoc generator for Tonic gRPC service code (`--tonic_out`).
+static RUST_GRPC_GENERATOR: &str = "tonic";
 
 /// Placeholder used in generated code for package names.
-static INCLUDE_MACRO_PLACEHOLDER: &'static str = "package_tonic";
+static INCLUDE_MACRO_PLACEHOLDER: &str = "package_tonic";
 
 /// Include macro pattern that needs to be removed from Prost-generated files.
-static RUST_INCLUDE_MACRO: &'static str = "include!(\"package_tonic\");";
+static RUST_INCLUDE_MACRO: &str = "include!(\"package_tonic\");";
 
 /// Use directive that needs to be added to Tonic-generated files.
-static TONIC_USE_SUPER_DIRECTIVE: &'static str = "use super::package_tonic::*;";
+static TONIC_USE_SUPER_DIRECTIVE: &str = "use super::package_tonic::*;\n";
+
+/// Suffix of the module holding the Tonic code of a package (e.g., `v1_tonic`).
+static RUST_TONIC_MODULE_SUFFIX: &str = "_tonic";
+
+/// Folder inside `messages_out` where protoc writes before post-processing.
+static RUST_STAGING_FOLDER: &str = ".protoweld_staging";
+
+/// File of the staging folder where protoc writes the descriptor set (library backend).
+static RUST_DESCRIPTOR_SET_FILENAME: &str = "descriptor_set.bin";
+
+/// Protoc flag writing the parsed proto files as a `FileDescriptorSet`.
+static DESCRIPTOR_SET_OUT_FLAG: &str = "--descriptor_set_out";
+
+/// Protoc flag adding the imported files to the descriptor set.
+static INCLUDE_IMPORTS_FLAG: &str = "--include_imports";
 
 /// Standard Rust module filename.
-static RUST_STANDARD_MODULE_FILENAME: &'static str = "mod.rs";
+static RUST_STANDARD_MODULE_FILENAME: &str = "mod.rs";
+
+/// Root module filename of a library crate.
+static RUST_LIBRARY_FILENAME: &str = "lib.rs";
+
+/// Macro including a generated file in the module of its package.
+static RUST_INCLUDE_MACRO_NAME: &str = "include!";
+
+/// Start of a public module declaration.
+static RUST_PUB_MOD_PREFIX: &str = "pub mod ";
 
 impl ProtobufCompiler for RustCompiler {
     /// Compiles proto files to Rust code with post-processing.
     ///
     /// This implementation:
     /// 1. Extracts package names from proto files
-    /// 2. Compiles proto files using Prost and Tonic
+    /// 2. Compiles proto files using Prost and Tonic into a fresh staging folder,
+    ///    either through their protoc plugins or, with `rust_options.backend: Library`,
+    ///    through `prost-build`/`tonic-build` on the descriptor set written by protoc
     /// 3. Performs post-processing to organize files into proper Rust modules:
-    ///    - Renames Tonic files from `package.tonic.rs` to `package_tonic.rs`
-    ///    - Removes include macros from Prost files
-    ///    - Adds use directives to Tonic files
-    ///    - Creates `mod.rs` files for each package
+    ///    - Moves each package into a nested folder (`acme.billing.v1` into `acme/billing/v1`)
+    ///    - Renames Tonic files from `package.tonic.rs` to `package_tonic.rs` (plugins only)
+    ///    - Removes include macros from Prost files (plugins only)
+    ///    - Adds use directives to Tonic files (plugins only)
+    ///    - Creates `mod.rs` files at every level of the module tree
+    /// 4. Removes the staging folder
     ///
     /// # Arguments
     ///
@@ -78,18 +136,37 @@ impl ProtobufCompiler for RustCompiler {
     ///
     /// # Generated Output Structure
     ///
-    /// For each package, creates:
-    /// - `package/package.rs` - Prost-generated message types
-    /// - `package/package_tonic.rs` - Tonic-generated gRPC services
-    /// - `package/mod.rs` - Module declarations
+    /// For each package (e.g., `acme.billing.v1`), creates in `messages_out`
+    /// (defaulting to `compiled_proto_folder`):
+    /// - `acme/billing/v1/v1.rs` - Prost-generated message types
+    /// - `acme/billing/v1/v1_tonic.rs` - Tonic-generated gRPC services (unless `messages_only`).
+    ///   The library backend writes them in `v1.rs` instead
+    /// - `acme/billing/v1/mod.rs` - Module declarations. The library backend
+    ///   includes `v1.rs` there instead of declaring it, so the types live in
+    ///   `acme::billing::v1` as prost's references to other packages expect
+    /// - `acme/mod.rs` and `acme/billing/mod.rs` - Declarations of the nested modules,
+    ///   shared by every package with the same prefix
+    ///
+    /// A root `mod.rs` (or `lib.rs`, see `rust_options.root_module`) declaring
+    /// every top-level package is also written in `messages_out`.
+    ///
+    /// Tonic modules reference the Prost module through `super`, so `grpc_out`
+    /// can't point to a different folder than `messages_out`.
     ///
     /// # Post-Processing Details
     ///
+    /// Post-processing only touches the files protoc just wrote in
+    /// `messages_out/.protoweld_staging`, and every step checks whether it was
+    /// already applied, so the compilation can run any number of times without
+    /// cleaning the output folder. Processed files replace the ones of previous runs.
+    ///
     /// The Rust code generators (Prost and Tonic) produce files that need
     /// organization to work properly in Rust's module system:
     ///
-    /// 1. **File Renaming**: Tonic generates files like `package.tonic.rs`,
-    ///    but Rust module names can't contain dots, so we rename to `package_tonic.rs`
+    /// 1. **File Renaming**: Prost and Tonic generate files like `acme.billing.v1.rs`
+    ///    and `acme.billing.v1.tonic.rs` in the output root, but Rust module names
+    ///    can't contain dots, so we move them to `acme/billing/v1/v1.rs` and
+    ///    `acme/billing/v1/v1_tonic.rs`
     ///
     /// 2. **Include Macro Removal**: Prost files include a macro like
     ///    `include!("package.tonic.rs");` which we remove since we're organizing
@@ -99,81 +176,519 @@ impl ProtobufCompiler for RustCompiler {
     ///    directive at the top to access the message types from the Prost module
     ///
     /// 4. **Module File Creation**: We create `mod.rs` files that declare both
-    ///    the message module and the gRPC module as public
-    fn compile_project(&self, project: &crate::parser::types::Project) -> Result<(), String> {
+    ///    the message module and the gRPC module as public, along with the
+    ///    nested packages (e.g., `acme/billing/mod.rs` declares `v1`)
+    fn compile_project(&self, project: &Project) -> Result<(), String> {
+        let generates_grpc = project.generates_grpc();
+        if generates_grpc && project.grpc_folder() != project.messages_folder() {
+            return Err(String::from(
+                "Rust projects must generate gRPC services in the same folder as messages, so grpc_out must match messages_out",
+            ));
+        }
+
         // Extract all unique package names from proto files
         let packages: HashSet<String> = self.get_packages_set(&project.associated_proto_files)?;
 
-        // Compile proto files using protoc with Prost and Tonic plugins
-        self.assemble_compilation(
-            project,
-            RUST_DEPS.to_vec(),
-            RUST_VERSION_FLAGS.to_vec(),
-            RUST_MESSAGES_OUT_ARGUMENT,
-            RUST_GRPC_OUT_ARGUMENT,
-            Option::None,
-        )?;
+        let base_path: PathBuf = project.messages_folder().into();
+
+        // protoc writes into a fresh staging folder, so files left by previous
+        // runs are never post-processed twice
+        let staging_path: PathBuf = base_path.join(RUST_STAGING_FOLDER);
+        self.os_manager().remove_path(&staging_path)?;
+
+        let backend: RustBackend = project.rust_options.backend;
+        match backend {
+            RustBackend::Plugins => self.generate_with_plugins(project, &staging_path)?,
+            RustBackend::Library => self.generate_with_libraries(project, &staging_path)?,
+        }
+
+        // The library backend writes services in the same file as messages
+        let separate_tonic_file = generates_grpc && backend == RustBackend::Plugins;
+
+        // Declarations of each module of the tree, keyed by its path from base_path
+        let mut modules: BTreeMap<Vec<String>, BTreeSet<String>> = BTreeMap::new();
 
-        let base_path: PathBuf = (&project.compiled_proto_folder).into();
+        // Packages whose generated file is included in their own module (library
+        // backend), so the relative paths prost emits (super::super::common::Money)
+        // resolve like in prost-build's own module layout
+        let mut included_files: BTreeMap<Vec<String>, String> = BTreeMap::new();
 
         // Post-process each package to organize files into proper Rust modules
         for pkg in packages.iter() {
-            // Build paths for Prost and Tonic files
-            let mut prost_file: PathBuf = base_path.clone();
-            prost_file.push(pkg);
-            let mut module_path: PathBuf = prost_file.clone();
-            prost_file.push(format!("{}.rs", &pkg));
+            let components: Vec<String> = pkg.split('.').map(String::from).collect();
+            if components.iter().any(|component| component.is_empty()) {
+                return Err(format!("Invalid package name {}", pkg));
+            }
+
+            // Packages mapped through extern_paths are not generated at all, but
+            // any other missing file means the generation went wrong
+            let staged_prost_file: PathBuf = staging_path.join(format!("{}.rs", pkg));
+            if !self.os_manager().is_file(&staged_prost_file) {
+                if is_extern_package(project, pkg) {
+                    debug!(
+                        "Package {} is mapped through extern_paths, skipping its module",
+                        pkg
+                    );
+                    continue;
+                }
+
+                return Err(format!(
+                    "No Rust code was generated for package {} (expected {})",
+                    pkg,
+                    staged_prost_file.display()
+                ));
+            }
+
+            // Every prefix declares the next component (acme -> billing -> v1)
+            for depth in 0..components.len() {
+                modules
+                    .entry(components[..depth].to_vec())
+                    .or_default()
+                    .insert(components[depth].clone());
+            }
+
+            let leaf: &String = components.last().unwrap();
+
+            let declarations = modules.entry(components.clone()).or_default();
+            match backend {
+                RustBackend::Plugins => {
+                    declarations.insert(leaf.clone());
+                }
+                RustBackend::Library => {
+                    included_files.insert(components.clone(), format!("{}.rs", leaf));
+                }
+            }
+            if separate_tonic_file {
+                declarations.insert(format!("{}{}", leaf, RUST_TONIC_MODULE_SUFFIX));
+            }
+
+            // Prost writes acme.billing.v1.rs in the staging root, which is moved
+            // to acme/billing/v1/v1.rs once processed
+            let mut module_folder: PathBuf = base_path.clone();
+            module_folder.extend(components.iter());
+            self.os_manager().create_folder(&module_folder)?;
+
+            let prost_file: PathBuf = module_folder.join(format!("{}.rs", leaf));
+            let tonic_file: PathBuf =
+                module_folder.join(format!("{}{}.rs", leaf, RUST_TONIC_MODULE_SUFFIX));
+
+            if !separate_tonic_file {
+                // Without a Tonic file there is only the Prost module to declare,
+                // and the Tonic module of a previous run must not be left behind
+                self.os_manager()
+                    .rename_file(&staged_prost_file, &prost_file)?;
+                self.os_manager().remove_path(&tonic_file)?;
+                continue;
+            }
 
             // Tonic generates files with .tonic.rs extension, but we need _tonic.rs
             // for proper Rust module naming (dots aren't allowed in module names)
-            let mut bad_tonic_file: PathBuf = base_path.clone();
-            bad_tonic_file.push(pkg);
-            let mut good_tonic_file: PathBuf = bad_tonic_file.clone();
-            let bad_tonic_filename: String = format!("{}.tonic.rs", &pkg);
-
-            bad_tonic_file.push(format!("{}.tonic.rs", &pkg));
-            good_tonic_file.push(format!("{}_tonic.rs", &pkg));
+            let bad_tonic_filename: String = format!("{}.tonic.rs", pkg);
+            let staged_tonic_file: PathBuf = staging_path.join(&bad_tonic_filename);
 
             debug!(
                 "prost file: {} and tonic file: {}",
-                &prost_file.to_str().unwrap(),
-                &bad_tonic_file.to_str().unwrap()
+                &staged_prost_file.to_str().unwrap(),
+                &staged_tonic_file.to_str().unwrap()
             );
 
-            // Rename Tonic file from package.tonic.rs to package_tonic.rs
-            self.os_manager()
-                .rename_file(&bad_tonic_file, &good_tonic_file)?;
-
             // Remove the include macro from Prost file
             // Prost generates: include!("package.tonic.rs");
             // We remove it since we're organizing modules manually
             self.os_manager().find_replace(
-                &prost_file,
+                &staged_prost_file,
                 RUST_INCLUDE_MACRO.replace(INCLUDE_MACRO_PLACEHOLDER, bad_tonic_filename.as_str()),
                 "".to_string(),
             )?;
 
-            // Add use directive to Tonic file to import message types
-            // This allows the gRPC code to use types from the Prost module
-            self.os_manager().insert_in_position(
-                &good_tonic_file,
-                0,
-                TONIC_USE_SUPER_DIRECTIVE.replace(INCLUDE_MACRO_PLACEHOLDER, &pkg),
+            // Add use directive to Tonic file to import message types, unless
+            // it's already there. This allows the gRPC code to use types from
+            // the Prost module
+            let use_directive: String =
+                TONIC_USE_SUPER_DIRECTIVE.replace(INCLUDE_MACRO_PLACEHOLDER, leaf);
+            if !self
+                .os_manager()
+                .read_file(&staged_tonic_file)?
+                .starts_with(&use_directive)
+            {
+                self.os_manager()
+                    .insert_in_position(&staged_tonic_file, 0, use_directive)?;
+            }
+
+            // Move the processed files into the module tree, replacing the previous ones
+            self.os_manager()
+                .rename_file(&staged_prost_file, &prost_file)?;
+            self.os_manager()
+                .rename_file(&staged_tonic_file, &tonic_file)?;
+        }
+
+        self.os_manager().remove_path(&staging_path)?;
+
+        // Create the mod.rs file of every module, merging packages sharing a prefix
+        for (path, declarations) in modules.iter() {
+            // The root module is written separately, since its file is configurable
+            if path.is_empty() {
+                continue;
+            }
+
+            let mut module_folder: PathBuf = base_path.clone();
+            module_folder.extend(path.iter());
+
+            self.write_module_file(
+                &module_folder,
+                RUST_STANDARD_MODULE_FILENAME,
+                included_files.get(path),
+                declarations,
             )?;
+        }
+
+        if let Some(root_declarations) = modules.get(&Vec::new()) {
+            self.write_root_module(project, &base_path, root_declarations)?;
+        }
 
-            // Create mod.rs file that declares both modules as public
-            let module_file_content: String = format!(
-                "pub mod {};\npub mod {};",
-                &pkg,
-                pkg.to_string() + &String::from("_tonic")
+        Ok(())
+    }
+    /// Requests the descriptor set used by the library backend, imports included
+    /// so `prost-build` can resolve every referenced type.
+    fn extra_protoc_arguments(&self, project: &Project) -> Vec<String> {
+        if project.rust_options.backend != RustBackend::Library {
+            return Vec::new();
+        }
+
+        let descriptor_set_file: PathBuf = PathBuf::from(project.messages_folder())
+            .join(RUST_STAGING_FOLDER)
+            .join(RUST_DESCRIPTOR_SET_FILENAME);
+
+        vec![
+            format!(
+                "{}={}",
+                DESCRIPTOR_SET_OUT_FLAG,
+                descriptor_set_file.to_string_lossy()
+            ),
+            String::from(INCLUDE_IMPORTS_FLAG),
+        ]
+    }
+}
+
+impl RustCompiler {
+    /// Runs `protoc-gen-prost` and `protoc-gen-tonic` through protoc, writing
+    /// `package.rs` and `package.tonic.rs` files into `staging_path`.
+    fn generate_with_plugins(&self, project: &Project, staging_path: &Path) -> Result<(), String> {
+        let generates_grpc = project.generates_grpc();
+        let staging_folder: String = staging_path.to_string_lossy().to_string();
+
+        let rust_options: &RustOptions = &project.rust_options;
+        let extern_path_options: Vec<String> = rust_options
+            .extern_paths
+            .iter()
+            .map(|extern_path| {
+                format!(
+                    "{}={}={}",
+                    PROST_EXTERN_PATH_OPTION, extern_path.proto_path, extern_path.rust_path
+                )
+            })
+            .collect();
+
+        let mut prost_options: Vec<String> = extern_path_options.clone();
+        for (option, attributes) in [
+            (PROST_TYPE_ATTRIBUTE_OPTION, &rust_options.type_attributes),
+            (PROST_FIELD_ATTRIBUTE_OPTION, &rust_options.field_attributes),
+        ] {
+            // Options are separated by commas, so the ones inside attributes (and
+            // the backslashes escaping them) are escaped
+            prost_options.extend(attributes.iter().map(|attribute| {
+                format!(
+                    "{}={}={}",
+                    option,
+                    attribute.path,
+                    attribute
+                        .attribute
+                        .replace('\\', "\\\\")
+                        .replace(',', "\\,")
+                )
+            }));
+        }
+        prost_options.extend(
+            rust_options
+                .bytes
+                .iter()
+                .map(|path| format!("{}={}", PROST_BYTES_OPTION, path)),
+        );
+
+        let mut outputs: Vec<ProtocOutput> = vec![ProtocOutput {
+            generator: String::from(RUST_MESSAGES_GENERATOR),
+            options: prost_options,
+            kind: OutputKind::Messages,
+            out_folder: Some(staging_folder.clone()),
+            ..Default::default()
+        }];
+
+        if generates_grpc {
+            // Tonic must resolve the extern request and response types like Prost
+            let mut tonic_options: Vec<String> = grpc_services_options(
+                project,
+                RUST_GRPC_GENERATOR,
+                Some(&RUST_CLIENT_ONLY_OPTIONS),
+                Some(&RUST_SERVER_ONLY_OPTIONS),
             );
+            tonic_options.extend(extern_path_options);
 
-            module_path.push(RUST_STANDARD_MODULE_FILENAME);
+            outputs.push(ProtocOutput {
+                generator: String::from(RUST_GRPC_GENERATOR),
+                options: tonic_options,
+                kind: OutputKind::Grpc,
+                out_folder: Some(staging_folder),
+                ..Default::default()
+            });
+        }
 
-            self.os_manager()
-                .write_new_file(&module_path, module_file_content)?;
+        let (deps, version_flags): (Vec<&'static str>, Vec<&'static str>) = RUST_DEPS
+            .iter()
+            .zip(RUST_VERSION_FLAGS.iter())
+            .filter(|(dep, _)| generates_grpc || **dep != RUST_GRPC_DEP)
+            .unzip();
+
+        // Compile proto files using protoc with Prost and Tonic plugins
+        self.assemble_compilation(project, deps, version_flags, outputs)
+    }
+
+    /// Runs `prost-build` (with `tonic-build` as its service generator) on the
+    /// descriptor set written by protoc, writing one `package.rs` file per
+    /// package into `staging_path`.
+    ///
+    /// Only `protoc` is required: the descriptor set is requested through
+    /// [`ProtobufCompiler::extra_protoc_arguments`].
+    fn generate_with_libraries(
+        &self,
+        project: &Project,
+        staging_path: &Path,
+    ) -> Result<(), String> {
+        self.os_manager().create_folder(staging_path)?;
+        self.assemble_compilation(
+            project,
+            RUST_DEPS[..1].to_vec(),
+            RUST_VERSION_FLAGS[..1].to_vec(),
+            Vec::new(),
+        )?;
+
+        let descriptor_set_file: PathBuf = staging_path.join(RUST_DESCRIPTOR_SET_FILENAME);
+        let descriptor_set: FileDescriptorSet = match self
+            .os_manager()
+            .read_bytes(&descriptor_set_file)
+        {
+            Ok(content) => match FileDescriptorSet::decode(content.as_slice()) {
+                Ok(descriptor_set) => descriptor_set,
+                Err(e) => return Err(format!("Invalid descriptor set written by protoc: {}", e)),
+            },
+            Err(e) => {
+                return Err(format!(
+                    "Failed to read the descriptor set written by protoc: {}",
+                    e
+                ))
+            }
+        };
+
+        let rust_options: &RustOptions = &project.rust_options;
+        let mut config = prost_build::Config::new();
+        config.out_dir(staging_path);
+        for extern_path in rust_options.extern_paths.iter() {
+            config.extern_path(&extern_path.proto_path, &extern_path.rust_path);
+        }
+        for attribute in rust_options.type_attributes.iter() {
+            config.type_attribute(&attribute.path, &attribute.attribute);
         }
+        for attribute in rust_options.field_attributes.iter() {
+            config.field_attribute(&attribute.path, &attribute.attribute);
+        }
+        config.bytes(rust_options.bytes.iter());
 
-        Ok(())
+        if project.generates_grpc() {
+            let grpc_services: GrpcServices = project.grpc_services;
+            config.service_generator(
+                tonic_build::configure()
+                    .build_client(grpc_services != GrpcServices::Server)
+                    .build_server(grpc_services != GrpcServices::Client)
+                    .service_generator(),
+            );
+        }
+
+        debug!(
+            "Generating Rust code of {} files with prost-build",
+            descriptor_set.file.len()
+        );
+
+        match config.compile_fds(descriptor_set) {
+            Err(e) => Err(format!("prost-build failed: {}", e)),
+            Ok(_) => Ok(()),
+        }
+    }
+
+    /// Writes (or updates) the root module file declaring every top-level package.
+    ///
+    /// The file is `mod.rs` or `lib.rs` depending on `rust_options.root_module`.
+    fn write_root_module(
+        &self,
+        project: &Project,
+        base_path: &Path,
+        declarations: &BTreeSet<String>,
+    ) -> Result<(), String> {
+        let filename: &'static str = match project.rust_options.root_module {
+            RustRootModule::ModRs => RUST_STANDARD_MODULE_FILENAME,
+            RustRootModule::LibRs => RUST_LIBRARY_FILENAME,
+            RustRootModule::None => return Ok(()),
+        };
+
+        self.write_module_file(base_path, filename, None, declarations)
+    }
+
+    /// Writes (or updates) the module file of `module_folder`, including the
+    /// generated file of its package (if any) and declaring its submodules.
+    ///
+    /// Folder submodules already declared in the file are kept while their
+    /// `mod.rs` exists, so projects or runs generating other packages with the
+    /// same prefix (e.g., `acme.billing` and `acme.auth`) don't remove each
+    /// other's modules, and running the compilation again yields the same file.
+    fn write_module_file(
+        &self,
+        module_folder: &Path,
+        filename: &str,
+        included_file: Option<&String>,
+        declarations: &BTreeSet<String>,
+    ) -> Result<(), String> {
+        let module_file: PathBuf = module_folder.join(filename);
+        let mut modules: BTreeSet<String> = declarations.clone();
+        if self.os_manager().is_file(&module_file) {
+            let content: String = self.os_manager().read_file(&module_file)?;
+            modules.extend(
+                declared_modules(&content)
+                    .filter(|module| {
+                        self.os_manager().is_file(
+                            &module_folder
+                                .join(module)
+                                .join(RUST_STANDARD_MODULE_FILENAME),
+                        )
+                    })
+                    .map(String::from),
+            );
+        }
+
+        debug!(
+            "Declaring {} modules in {}",
+            modules.len(),
+            module_file.display()
+        );
+
+        self.os_manager()
+            .write_new_file(&module_file, module_file_content(included_file, &modules))
+    }
+}
+
+/// Returns the modules declared with `pub mod name;` in a module file.
+fn declared_modules(content: &str) -> impl Iterator<Item = &str> {
+    content.lines().filter_map(|line| {
+        line.trim()
+            .strip_prefix(RUST_PUB_MOD_PREFIX)?
+            .strip_suffix(';')
+    })
+}
+
+/// Formats a module file: the `include!` of the package's generated file, if
+/// any, followed by the declarations of its submodules.
+fn module_file_content(included_file: Option<&String>, declarations: &BTreeSet<String>) -> String {
+    included_file
+        .map(|included_file| format!("{}(\"{}\");", RUST_INCLUDE_MACRO_NAME, included_file))
+        .into_iter()
+        .chain(
+            declarations
+                .iter()
+                .map(|declaration| format!("{}{};", RUST_PUB_MOD_PREFIX, declaration)),
+        )
+        .collect::<Vec<String>>()
+        .join("\n")
+}
+
+/// Checks whether a package is covered by an `extern_paths` entry, either
+/// directly or through one of its parent packages (`.` covers every package).
+fn is_extern_package(project: &Project, pkg: &str) -> bool {
+    project.rust_options.extern_paths.iter().any(|extern_path| {
+        let extern_package: &str = extern_path.proto_path.trim_start_matches('.');
+        extern_package.is_empty()
+            || pkg == extern_package
+            || pkg
+                .strip_prefix(extern_package)
+                .is_some_and(|rest| rest.starts_with('.'))
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        compilers::langs_compilers::compiler_types::CompilerParams, os::types::UnixManager,
+        parser::types::Lang,
+    };
+    use std::fs;
+
+    fn compiler() -> RustCompiler {
+        RustCompiler {
+            params: CompilerParams {
+                os_manager: Box::new(UnixManager),
+                input_file_path: PathBuf::from("protoweld.yaml"),
+                lang: Lang::Rust,
+            },
+        }
+    }
+
+    fn modules(names: &[&str]) -> BTreeSet<String> {
+        names.iter().map(|name| name.to_string()).collect()
+    }
+
+    #[test]
+    fn formats_module_files() {
+        let included_file: String = String::from("v1.rs");
+
+        assert_eq!(
+            module_file_content(Some(&included_file), &modules(&["v1_tonic"])),
+            "include!(\"v1.rs\");\npub mod v1_tonic;"
+        );
+        assert_eq!(
+            module_file_content(None, &modules(&["billing", "auth"])),
+            "pub mod auth;\npub mod billing;"
+        );
+        assert_eq!(
+            declared_modules(
+                "include!(\"v1.rs\");\npub mod auth;\n  pub mod billing;\nmod private;"
+            )
+            .collect::<Vec<&str>>(),
+            vec!["auth", "billing"]
+        );
+    }
+
+    #[test]
+    fn merges_modules_sharing_a_prefix() {
+        let root: PathBuf =
+            std::env::temp_dir().join(format!("protoweld-rust-modules-{}", std::process::id()));
+        let acme: PathBuf = root.join("acme");
+        for package in ["billing", "auth", "removed"] {
+            fs::create_dir_all(acme.join(package)).unwrap();
+            fs::write(acme.join(package).join("mod.rs"), "").unwrap();
+        }
+        let compiler: RustCompiler = compiler();
+        let module_file = |declarations: &[&str]| {
+            compiler
+                .write_module_file(&acme, "mod.rs", None, &modules(declarations))
+                .unwrap();
+            fs::read_to_string(acme.join("mod.rs")).unwrap()
+        };
+
+        let first_run: String = module_file(&["billing", "removed"]);
+        fs::remove_dir_all(acme.join("removed")).unwrap();
+        let second_run: String = module_file(&["auth"]);
+        let third_run: String = module_file(&["auth"]);
+        fs::remove_dir_all(&root).unwrap();
+
+        assert_eq!(first_run, "pub mod billing;\npub mod removed;");
+        assert_eq!(second_run, "pub mod auth;\npub mod billing;");
+        assert_eq!(third_run, second_run);
     }
 }
@@ -0,0 +1,435 @@
+//! TypeScript language compiler implementation with post-processing.
+//!
+//! This compiler generates TypeScript code using either `ts-proto` or
+//! `protobuf-es`/`connect-es`. After generation it writes an `index.ts`
+//! barrel per proto package, similar to the `mod.rs` files of the Rust compiler.
+
+use std::{
+    collections::{BTreeMap, BTreeSet, HashSet},
+    path::{Component, Path, PathBuf},
+};
+
+use log::{debug, warn};
+
+use crate::{
+    compilers::{
+        langs_compilers::compiler_types::{OutputKind, ProtocOutput, TypeScriptCompiler},
+        protobuf_compiler::{CompilerProperties, ProtobufCompiler},
+        shared::{grpc_services_options, relative_path},
+    },
+    os::types::OSManager,
+    parser::{
+        protoweld_parser::is_executable_name,
+        types::{Project, TypeScriptGenerator},
+    },
+};
+
+impl CompilerProperties for TypeScriptCompiler {
+    fn os_manager(&self) -> &dyn crate::os::types::OSManager {
+        self.params.os_manager.as_ref()
+    }
+
+    fn input_file_path(&self) -> &PathBuf {
+        &self.params.input_file_path
+    }
+}
+
+/// Required dependencies for TypeScript proto compilation.
+///
+/// These tools must be installed and available:
+/// - `protoc`: Protocol Buffers compiler
+/// - `node`: Node.js runtime used by the generator plugins
+static TYPESCRIPT_DEPS: [&str; 2] = ["protoc", "node"];
+
+/// Version flags for checking each dependency.
+static TYPESCRIPT_VERSION_FLAGS: [&str; 2] = ["--version", "--version"];
+
+/// Protoc generator for `ts-proto` (`--ts_proto_out`).
+static TS_PROTO_GENERATOR: &str = "ts_proto";
+
+/// Protoc generator for `protobuf-es` messages (`--es_out`).
+static PROTOBUF_ES_GENERATOR: &str = "es";
+
+/// Protoc generator for `connect-es` services (`--connect-es_out`).
+static CONNECT_ES_GENERATOR: &str = "connect-es";
+
+/// `ts-proto` option that stops exporting `protobufPackage` from every file,
+/// which would otherwise make `export *` barrels ambiguous.
+static TS_PROTO_BARREL_OPTION: &str = "exportCommonSymbols=false";
+
+/// `ts-proto` option that skips service definitions in `messages_only` mode.
+static TS_PROTO_NO_SERVICES_OPTION: &str = "outputServices=false";
+
+/// `ts-proto` options keeping the service interfaces but not the client
+/// implementations, for `grpc_services: Server`.
+static TS_PROTO_SERVER_ONLY_OPTIONS: [&str; 1] = ["outputClientImpl=false"];
+
+/// `protobuf-es`/`connect-es` option to emit TypeScript instead of JavaScript.
+static PROTOBUF_ES_TARGET_OPTION: &str = "target=ts";
+
+/// Folder where npm installs the executables of local packages.
+static NODE_MODULES_BIN_FOLDER: &str = "node_modules/.bin";
+
+/// Extension of the generated TypeScript files.
+static TYPESCRIPT_EXTENSION: &str = "ts";
+
+/// Standard TypeScript barrel filename.
+static TYPESCRIPT_BARREL_FILENAME: &str = "index.ts";
+
+/// Returns the protoc generators used by a flavour, along with the suffix
+/// each one appends to the generated filename (e.g., `users_pb.ts`) and the
+/// kind of code it produces.
+fn flavour_generators(
+    generator: TypeScriptGenerator,
+) -> Vec<(&'static str, &'static str, OutputKind)> {
+    match generator {
+        TypeScriptGenerator::TsProto => vec![(TS_PROTO_GENERATOR, "", OutputKind::Messages)],
+        TypeScriptGenerator::ProtobufEs => {
+            vec![(PROTOBUF_ES_GENERATOR, "_pb", OutputKind::Messages)]
+        }
+        TypeScriptGenerator::ConnectEs => vec![
+            (PROTOBUF_ES_GENERATOR, "_pb", OutputKind::Messages),
+            (CONNECT_ES_GENERATOR, "_connect", OutputKind::Grpc),
+        ],
+    }
+}
+
+impl ProtobufCompiler for TypeScriptCompiler {
+    /// Compiles proto files to TypeScript code with post-processing.
+    ///
+    /// This implementation:
+    /// 1. Locates the generator plugins of the configured flavour
+    /// 2. Compiles proto files using protoc with those plugins
+    /// 3. Writes an `index.ts` barrel for each proto package
+    ///
+    /// # Arguments
+    ///
+    /// * `project` - Project configuration with TypeScript-specific settings
+    ///
+    /// # Returns
+    ///
+    /// * `Ok(())` - TypeScript code generated successfully
+    /// * `Err(String)` - Error message if compilation or post-processing fails
+    ///
+    /// # Plugin Discovery
+    ///
+    /// For each `protoc-gen-<generator>` executable, in order:
+    /// 1. `plugin_path`, when it is a directory containing the executable
+    /// 2. `plugin_path`, when it is a file or an executable in PATH (first
+    ///    generator of the flavour only)
+    /// 3. `node_modules/.bin/protoc-gen-<generator>` in the project's base folder
+    /// 4. `protoc-gen-<generator>` in PATH
+    ///
+    /// # Generated Output Structure
+    ///
+    /// For each package `a.b`, creates `a/b/index.ts` in `messages_out` re-exporting
+    /// every file generated from the proto files declaring that package, including
+    /// the service files generated in `grpc_out`. With `messages_only`, services
+    /// are skipped: `connect-es` is not run and `ts-proto` omits them. Only
+    /// `ts-proto` can restrict services to `grpc_services`, the other flavours
+    /// always describe both sides and log a warning.
+    fn compile_project(&self, project: &Project) -> Result<(), String> {
+        let generates_grpc = project.generates_grpc();
+        let generators: Vec<(&'static str, &'static str, OutputKind)> =
+            flavour_generators(project.typescript_options.generator)
+                .into_iter()
+                .filter(|(_, _, kind)| generates_grpc || *kind != OutputKind::Grpc)
+                .collect();
+
+        // The last generator of a flavour is the one writing the services
+        let services_generator: Option<&'static str> =
+            generators.last().map(|(generator, _, _)| *generator);
+
+        let outputs: Vec<ProtocOutput> = generators
+            .iter()
+            .enumerate()
+            .map(|(index, (generator, _, kind))| {
+                let mut options: Vec<String> = match *generator == TS_PROTO_GENERATOR {
+                    true => vec![String::from(TS_PROTO_BARREL_OPTION)],
+                    false => vec![String::from(PROTOBUF_ES_TARGET_OPTION)],
+                };
+                if *generator == TS_PROTO_GENERATOR {
+                    match generates_grpc {
+                        true => options.extend(grpc_services_options(
+                            project,
+                            TS_PROTO_GENERATOR,
+                            Some(&[]),
+                            Some(&TS_PROTO_SERVER_ONLY_OPTIONS),
+                        )),
+                        false => options.push(String::from(TS_PROTO_NO_SERVICES_OPTION)),
+                    }
+                } else if generates_grpc && services_generator == Some(*generator) {
+                    options.extend(grpc_services_options(project, generator, None, None));
+                }
+
+                Ok(ProtocOutput {
+                    generator: String::from(*generator),
+                    options,
+                    plugin_path: Some(locate_plugin(
+                        self.os_manager(),
+                        project,
+                        generator,
+                        index == 0,
+                    )?),
+                    kind: *kind,
+                    ..Default::default()
+                })
+            })
+            .collect::<Result<Vec<ProtocOutput>, String>>()?;
+
+        let output_folders: Vec<PathBuf> = outputs
+            .iter()
+            .map(|output| PathBuf::from(output.folder(project)))
+            .collect();
+
+        self.assemble_compilation(
+            project,
+            TYPESCRIPT_DEPS.to_vec(),
+            TYPESCRIPT_VERSION_FLAGS.to_vec(),
+            outputs,
+        )?;
+
+        let mut generated_files: HashSet<PathBuf> = HashSet::new();
+        for folder in output_folders.iter().collect::<BTreeSet<&PathBuf>>() {
+            generated_files.extend(self.os_manager().list_files(folder)?);
+        }
+
+        // Group the generated modules (paths without extension) by proto package
+        let mut barrels: BTreeMap<String, BTreeSet<PathBuf>> = BTreeMap::new();
+        for proto in project.associated_proto_files.iter() {
+            let package = self.get_package(proto)?;
+            let proto_path = PathBuf::from(proto);
+            let stem = proto_path
+                .file_stem()
+                .unwrap_or_default()
+                .to_string_lossy()
+                .to_string();
+
+            let proto_dirs: Vec<String> = proto_path
+                .parent()
+                .map(|parent| {
+                    parent
+                        .components()
+                        .filter_map(|component| match component {
+                            Component::Normal(name) => Some(name.to_string_lossy().to_string()),
+                            _ => None,
+                        })
+                        .collect()
+                })
+                .unwrap_or_default();
+
+            for ((_, suffix, _), folder) in generators.iter().zip(output_folders.iter()) {
+                let module = format!("{}{}", stem, suffix);
+
+                // protoc names outputs after the proto path relative to its include
+                // root, which is a suffix of the path written in the configuration
+                let generated = (0..=proto_dirs.len())
+                    .map(|skip| {
+                        let mut module_path: PathBuf = folder.clone();
+                        module_path.extend(proto_dirs[skip..].iter());
+                        module_path.push(&module);
+                        module_path
+                    })
+                    .find(|module_path| {
+                        generated_files.contains(&module_path.with_extension(TYPESCRIPT_EXTENSION))
+                    });
+
+                match generated {
+                    Some(module_path) => {
+                        barrels
+                            .entry(package.clone())
+                            .or_default()
+                            .insert(module_path);
+                    }
+                    None => warn!("No TypeScript output {} found for {}", module, proto),
+                }
+            }
+        }
+
+        // Write one index.ts per package re-exporting its modules
+        let base_path: PathBuf = project.messages_folder().into();
+        for (package, modules) in barrels.iter() {
+            let mut barrel_dir: PathBuf = base_path.clone();
+            barrel_dir.extend(package.split('.'));
+
+            let barrel_content: String = modules
+                .iter()
+                .map(|module_path| {
+                    format!(
+                        "export * from \"{}\";\n",
+                        module_specifier(&barrel_dir, module_path)
+                    )
+                })
+                .collect();
+
+            self.os_manager().create_folder(&barrel_dir)?;
+            let barrel_file: PathBuf = barrel_dir.join(TYPESCRIPT_BARREL_FILENAME);
+            debug!("Writing TypeScript barrel {}", barrel_file.display());
+
+            self.os_manager()
+                .write_new_file(&barrel_file, barrel_content)?;
+        }
+
+        Ok(())
+    }
+}
+
+/// Finds the executable of a generator plugin following the discovery order
+/// described in [`TypeScriptCompiler::compile_project`].
+///
+/// # Errors
+///
+/// Returns an error if the plugin is found in none of those places.
+fn locate_plugin(
+    os_manager: &dyn OSManager,
+    project: &Project,
+    generator: &str,
+    primary: bool,
+) -> Result<String, String> {
+    let plugin_name = format!("protoc-gen-{}", generator);
+
+    if let Some(plugin_path) = &project.plugin_path {
+        let candidate = PathBuf::from(plugin_path).join(&plugin_name);
+        if os_manager.is_file(&candidate) {
+            return Ok(candidate.to_string_lossy().to_string());
+        }
+        if primary {
+            if os_manager.is_file(Path::new(plugin_path)) {
+                return Ok(plugin_path.clone());
+            }
+            if let Some(plugin) = is_executable_name(plugin_path)
+                .then(|| os_manager.find_executable(plugin_path))
+                .flatten()
+            {
+                return Ok(plugin.to_string_lossy().to_string());
+            }
+        }
+    }
+
+    let local_plugin = project
+        .base_dir
+        .join(NODE_MODULES_BIN_FOLDER)
+        .join(&plugin_name);
+    if os_manager.is_file(&local_plugin) {
+        return Ok(local_plugin.to_string_lossy().to_string());
+    }
+
+    match os_manager.find_executable(&plugin_name) {
+        Some(plugin) => Ok(plugin.to_string_lossy().to_string()),
+        None => Err(format!(
+            "The plugin {} was not found in plugin_path, {} or PATH",
+            plugin_name, NODE_MODULES_BIN_FOLDER
+        )),
+    }
+}
+
+/// Builds the import specifier from a barrel folder to a generated module,
+/// given as its path without extension.
+///
+/// For example, from `out/security` to `out/protos/security/auth`
+/// the result is `../protos/security/auth`.
+fn module_specifier(barrel_dir: &Path, module_path: &Path) -> String {
+    let specifier = relative_path(barrel_dir, module_path)
+        .components()
+        .map(|component| component.as_os_str().to_string_lossy().to_string())
+        .collect::<Vec<String>>()
+        .join("/");
+
+    match specifier.starts_with("..") {
+        true => specifier,
+        false => format!("./{}", specifier),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::os::types::UnixManager;
+    use std::fs;
+
+    fn project(plugin_path: Option<&Path>, base_dir: &Path) -> Project {
+        let mut project: Project = serde_yaml::from_str(&format!(
+            "path: web\nlang: TypeScript\ncompiled_proto_folder: out\nassociated_proto_files: []\nplugin_path: {}\n",
+            plugin_path
+                .map(|path| path.display().to_string())
+                .unwrap_or_else(|| String::from("null"))
+        ))
+        .unwrap();
+        project.base_dir = base_dir.to_path_buf();
+        project
+    }
+
+    #[test]
+    fn selects_the_generators_of_each_flavour() {
+        let generators = |flavour: TypeScriptGenerator| -> Vec<&'static str> {
+            flavour_generators(flavour)
+                .into_iter()
+                .map(|(generator, _, _)| generator)
+                .collect()
+        };
+
+        assert_eq!(generators(TypeScriptGenerator::TsProto), vec!["ts_proto"]);
+        assert_eq!(generators(TypeScriptGenerator::ProtobufEs), vec!["es"]);
+        assert_eq!(
+            generators(TypeScriptGenerator::ConnectEs),
+            vec!["es", "connect-es"]
+        );
+        assert_eq!(
+            flavour_generators(TypeScriptGenerator::ConnectEs)[1],
+            ("connect-es", "_connect", OutputKind::Grpc)
+        );
+    }
+
+    #[test]
+    fn locates_plugins_in_plugin_path_then_node_modules() {
+        let root: PathBuf =
+            std::env::temp_dir().join(format!("protoweld-ts-plugins-{}", std::process::id()));
+        let plugins: PathBuf = root.join("plugins");
+        let node_modules: PathBuf = root.join(NODE_MODULES_BIN_FOLDER);
+        fs::create_dir_all(&plugins).unwrap();
+        fs::create_dir_all(&node_modules).unwrap();
+        for plugin in [
+            plugins.join("protoc-gen-connect-es"),
+            plugins.join("protoc-gen-custom"),
+            node_modules.join("protoc-gen-es"),
+            node_modules.join("protoc-gen-connect-es"),
+        ] {
+            fs::write(plugin, "").unwrap();
+        }
+        let locate = |plugin_path: Option<&Path>, generator: &str, primary: bool| {
+            locate_plugin(
+                &UnixManager,
+                &project(plugin_path, &root),
+                generator,
+                primary,
+            )
+        };
+
+        let from_folder = locate(Some(&plugins), "connect-es", false);
+        let folder_fallback = locate(Some(&plugins), "es", true);
+        let from_file = locate(Some(&plugins.join("protoc-gen-custom")), "es", true);
+        let file_not_primary = locate(
+            Some(&plugins.join("protoc-gen-custom")),
+            "connect-es",
+            false,
+        );
+        let missing = locate(None, "protoweld-missing", true);
+        fs::remove_dir_all(&root).unwrap();
+
+        let display = |path: PathBuf| path.display().to_string();
+        assert_eq!(
+            from_folder,
+            Ok(display(plugins.join("protoc-gen-connect-es")))
+        );
+        assert_eq!(
+            folder_fallback,
+            Ok(display(node_modules.join("protoc-gen-es")))
+        );
+        assert_eq!(from_file, Ok(display(plugins.join("protoc-gen-custom"))));
+        assert_eq!(
+            file_not_primary,
+            Ok(display(node_modules.join("protoc-gen-connect-es")))
+        );
+        assert!(missing.is_err());
+    }
+}
@@ -2,13 +2,34 @@
 
 use std::{collections::HashSet, path::PathBuf};
 
-use crate::{os::types::OSManager, parser::types::Project};
+use log::debug;
 
-/// Keyword used to identify package declarations in proto files.
-static PACKAGE_KEYWORD: &'static str = "package";
+use crate::{
+    compilers::{langs_compilers::compiler_types::ProtocOutput, shared::relative_path},
+    os::types::OSManager,
+    parser::{
+        protoweld_parser::is_executable_name,
+        types::{Project, PROTO_PATH_FLAG, PROTO_PATH_SHORT_FLAG},
+    },
+    proto::{ast::ProtoFile, parser::parse_file},
+};
 
-/// Character used to terminate package declarations in proto files.
-static SEMICOLON: &'static str = ";";
+/// Generators built into protoc, which don't need a `protoc-gen-<name>` plugin.
+static PROTOC_BUILTIN_GENERATORS: [&str; 11] = [
+    "cpp", "csharp", "java", "kotlin", "objc", "php", "pyi", "python", "ruby", "rust", "upb",
+];
+
+/// Prefix protoc adds to a generator name to find its plugin executable.
+static PROTOC_PLUGIN_PREFIX: &str = "protoc-gen-";
+
+/// Default command used to invoke the Protocol Buffers compiler.
+static PROTOC_COMMAND: [&str; 1] = ["protoc"];
+
+/// Folder, relative to the `protoc` binary, where the well-known types may be installed.
+static PROTOC_INCLUDE_CANDIDATES: [&str; 2] = ["include", "../include"];
+
+/// File present in every folder holding the well-known types.
+static WELL_KNOWN_TYPES_MARKER: &str = "google/protobuf/descriptor.proto";
 
 /// Trait providing access to compiler properties and dependencies.
 ///
@@ -16,7 +37,7 @@ static SEMICOLON: &'static str = ";";
 /// which are needed by all compiler implementations.
 pub trait CompilerProperties {
     /// Returns a reference to the OS manager for system operations.
-    fn os_manager(&self) -> &Box<dyn OSManager>;
+    fn os_manager(&self) -> &dyn OSManager;
 
     /// Returns the canonicalized path to the input configuration file.
     fn input_file_path(&self) -> &PathBuf;
@@ -43,11 +64,63 @@ pub trait ProtobufCompiler: CompilerProperties {
     /// * `Ok(())` - Compilation succeeded
     /// * `Err(String)` - Error message if compilation fails
     fn compile_project(&self, project: &Project) -> Result<(), String>;
+    /// Returns the command used to invoke `protoc`.
+    ///
+    /// The first element is the executable and the remaining elements are
+    /// arguments placed before any compilation flag. Compilers that ship their
+    /// own protoc (e.g., Python's `grpc_tools.protoc`) override this.
+    fn protoc_command(&self) -> Vec<&'static str> {
+        PROTOC_COMMAND.to_vec()
+    }
+    /// Returns protoc arguments that don't belong to any generator output
+    /// (e.g., `--descriptor_set_out`), added right before the output flags.
+    ///
+    /// # Arguments
+    ///
+    /// * `project` - Project being compiled
+    fn extra_protoc_arguments(&self, _project: &Project) -> Vec<String> {
+        Vec::new()
+    }
+    /// Finds the folder holding the well-known types (`google/protobuf/*.proto`).
+    ///
+    /// The `protoc` executable is resolved through PATH (following symlinks) and
+    /// the `include/` folders next to it are checked for the well-known types.
+    /// Compilers whose protoc bundles them (e.g., Python's `grpc_tools.protoc`)
+    /// override this to return `None`.
+    ///
+    /// # Returns
+    ///
+    /// * `Some(PathBuf)` - Folder to add to the import search path
+    /// * `None` - protoc is not in PATH or its includes are not installed
+    fn well_known_types_include(&self) -> Option<PathBuf> {
+        let protoc = self.os_manager().find_executable(PROTOC_COMMAND[0])?;
+        let protoc = protoc.canonicalize().unwrap_or(protoc);
+        let protoc_dir = protoc.parent()?;
+
+        PROTOC_INCLUDE_CANDIDATES
+            .iter()
+            .map(|candidate| protoc_dir.join(candidate))
+            .find(|include| include.join(WELL_KNOWN_TYPES_MARKER).is_file())
+            .map(|include| include.canonicalize().unwrap_or(include))
+    }
+    /// Parses a proto file into its declarations.
+    ///
+    /// # Arguments
+    ///
+    /// * `proto` - Path to the proto file
+    ///
+    /// # Returns
+    ///
+    /// * `Ok(ProtoFile)` - Package, options, imports, messages, enums and services of the file
+    /// * `Err(String)` - Error if the proto file cannot be read or has a syntax error
+    fn parse_proto(&self, proto: &String) -> Result<ProtoFile, String> {
+        parse_file(&PathBuf::from(proto))
+    }
     /// Extracts package names from a list of proto files.
     ///
-    /// This function searches each proto file for the `package` declaration
-    /// and extracts the package name. It's used primarily by the Rust compiler
-    /// to organize generated files into proper module structures.
+    /// This function reads the `package` declaration of each proto file.
+    /// It's used primarily by the Rust compiler to organize generated files
+    /// into proper module structures.
     ///
     /// # Arguments
     ///
@@ -58,57 +131,221 @@ pub trait ProtobufCompiler: CompilerProperties {
     /// * `Ok(HashSet<String>)` - Set of unique package names found
     /// * `Err(String)` - Error if a proto file is missing a package declaration
     ///
-    /// # Process
+    /// # Errors
     ///
-    /// 1. Searches each proto file for the "package" keyword
-    /// 2. Extracts the package name (text between "package" and ";")
-    /// 3. Collects unique package names into a HashSet
+    /// Returns an error if any file fails in [`ProtobufCompiler::get_package`].
+    fn get_packages_set(&self, protos: &[String]) -> Result<HashSet<String>, String> {
+        let mut result: HashSet<String> = HashSet::new();
+        for proto in protos.iter() {
+            result.insert(self.get_package(proto)?);
+        }
+
+        Ok(result)
+    }
+    /// Extracts the package name declared in a single proto file.
+    ///
+    /// # Arguments
+    ///
+    /// * `proto` - Path to the proto file
+    ///
+    /// # Returns
+    ///
+    /// * `Ok(String)` - Package name
+    /// * `Err(String)` - Error if the proto file is missing a package declaration
     ///
     /// # Errors
     ///
     /// Returns an error if:
-    /// - A proto file cannot be read
-    /// - A proto file doesn't contain a package declaration
-    /// - The package declaration is malformed
-    fn get_packages_set(&self, protos: &Vec<String>) -> Result<HashSet<String>, String> {
-        let mut result: HashSet<String> = HashSet::new();
-        for proto in protos.iter() {
-            let find_package = self
-                .os_manager()
-                .grep(PathBuf::from(&proto), PACKAGE_KEYWORD);
-            if let Err(e) = find_package {
-                return Err(e);
+    /// - The proto file cannot be read or has a syntax error
+    /// - The proto file doesn't contain a package declaration
+    fn get_package(&self, proto: &String) -> Result<String, String> {
+        match self.parse_proto(proto)?.package {
+            Some(package) => Ok(package),
+            None => Err(format!("Package declaration missing in {}", proto)),
+        }
+    }
+    /// Extracts the value of a file-level option declared in a proto file.
+    ///
+    /// # Arguments
+    ///
+    /// * `proto` - Path to the proto file
+    /// * `option` - Option name (e.g., "java_package", "go_package")
+    ///
+    /// # Returns
+    ///
+    /// * `Ok(Some(String))` - Option value, without surrounding quotes
+    /// * `Ok(None)` - The option is not declared in the file
+    /// * `Err(String)` - Error if the proto file cannot be read or has a syntax error
+    fn get_option(&self, proto: &String, option: &'static str) -> Result<Option<String>, String> {
+        Ok(self.parse_proto(proto)?.option(option).cloned())
+    }
+    /// Resolves a `plugin_path` into the executable given to protoc.
+    ///
+    /// protoc runs `--plugin` executables without searching PATH, so a
+    /// `plugin_path` without any `/` is taken as an executable name and looked
+    /// up in PATH. Other values are paths and are returned unchanged.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if an executable name is not in PATH.
+    fn plugin_executable(&self, plugin_path: &String) -> Result<String, String> {
+        if !is_executable_name(plugin_path) {
+            return Ok(plugin_path.clone());
+        }
+
+        match self.os_manager().find_executable(plugin_path) {
+            Some(found) => Ok(found.to_string_lossy().to_string()),
+            None => Err(format!(
+                "The plugin {} given in plugin_path is not in PATH",
+                plugin_path
+            )),
+        }
+    }
+    /// Converts the generators declared in the project's `generators` field into protoc outputs.
+    ///
+    /// A `plugin_path` without any `/` is taken as an executable name and resolved
+    /// through PATH, so both `/opt/bin/grpc_cpp_plugin` and `grpc_cpp_plugin` work.
+    ///
+    /// # Arguments
+    ///
+    /// * `project` - Project configuration with the declared generators
+    ///
+    /// # Returns
+    ///
+    /// * `Ok(Vec<ProtocOutput>)` - One output per declared generator, in declaration order
+    /// * `Err(String)` - Error message if a generator is invalid or its plugin is missing
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if:
+    /// - A generator has an empty name or is declared twice
+    /// - A `plugin_path` given as an executable name is not in PATH
+    fn configured_outputs(&self, project: &Project) -> Result<Vec<ProtocOutput>, String> {
+        let mut outputs: Vec<ProtocOutput> = Vec::new();
+        let mut missing_plugins: Vec<String> = Vec::new();
+
+        for generator in project.generators.iter() {
+            if generator.name.is_empty() {
+                return Err(format!(
+                    "Project {} declares a generator without name",
+                    project.path
+                ));
             }
 
-            let grep_result: (String, usize) = find_package.unwrap();
+            let plugin_path: Option<String> = match &generator.plugin_path {
+                Some(plugin_path) => match self.plugin_executable(plugin_path) {
+                    Ok(plugin_path) => Some(plugin_path),
+                    Err(_) => {
+                        missing_plugins.push(plugin_path.clone());
+                        None
+                    }
+                },
+                None => None,
+            };
 
-            if grep_result.1 == usize::MAX {
-                return Err(String::from(format!(
-                    "Package keyword missing in {}",
-                    &proto
-                )));
+            if outputs.iter().any(|output| output.generator == generator.name) {
+                return Err(format!(
+                    "Project {} declares the generator {} more than once",
+                    project.path, generator.name
+                ));
             }
 
-            let cut_string = grep_result
-                .0
-                .clone()
-                .split_off(grep_result.1)
-                .split_off(PACKAGE_KEYWORD.len() + 1);
+            outputs.push(ProtocOutput {
+                generator: generator.name.clone(),
+                options: generator.options.clone(),
+                plugin_path,
+                out_folder: generator.out_folder.clone(),
+                ..Default::default()
+            });
+        }
 
-            let mut semicolon_split = cut_string.split(SEMICOLON);
+        if !missing_plugins.is_empty() {
+            return Err(format!(
+                "Failed to check installation of the following dependencies: {}",
+                missing_plugins.join(",")
+            ));
+        }
 
-            match semicolon_split.next() {
-                Some(name) => result.insert(name.into()),
-                None => return Err(String::from("Bad .proto structure - Keyword package")),
-            };
+        Ok(outputs)
+    }
+    /// Merges the generators declared in the configuration into the outputs of a language compiler.
+    ///
+    /// A declared generator named like one of the language outputs (e.g., `go`
+    /// for a `GoLang` project) adds its options to that output and may replace
+    /// its plugin path. Any other declared generator is appended, so all of them
+    /// run in a single protoc invocation.
+    ///
+    /// # Arguments
+    ///
+    /// * `project` - Project configuration with the declared generators
+    /// * `compiler_outputs` - Outputs required by the language compiler
+    ///
+    /// # Returns
+    ///
+    /// * `Ok(Vec<ProtocOutput>)` - Language outputs followed by the additional generators
+    /// * `Err(String)` - Error message if a declared generator is invalid
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if:
+    /// - [`ProtobufCompiler::configured_outputs`] fails
+    /// - A declared generator tries to move a language output to another folder
+    ///   (the language compiler post-processes those outputs in place)
+    /// - An additional generator has no `plugin_path` and `protoc-gen-<name>` is
+    ///   not in PATH (protoc built-in generators excepted)
+    fn merge_outputs(
+        &self,
+        project: &Project,
+        compiler_outputs: Vec<ProtocOutput>,
+    ) -> Result<Vec<ProtocOutput>, String> {
+        let mut outputs: Vec<ProtocOutput> = compiler_outputs;
+        let mut missing_plugins: Vec<String> = Vec::new();
+
+        for configured in self.configured_outputs(project)?.into_iter() {
+            match outputs
+                .iter_mut()
+                .find(|output| output.generator == configured.generator)
+            {
+                Some(output) => {
+                    if configured.out_folder.is_some() {
+                        return Err(format!(
+                            "The generator {} is handled by the {:?} compiler. Its output folder can't be changed in generators",
+                            configured.generator, project.lang
+                        ));
+                    }
+
+                    output.options.extend(configured.options);
+                    if configured.plugin_path.is_some() {
+                        output.plugin_path = configured.plugin_path;
+                    }
+                }
+                None => {
+                    let plugin_name = format!("{}{}", PROTOC_PLUGIN_PREFIX, configured.generator);
+                    if configured.plugin_path.is_none()
+                        && !PROTOC_BUILTIN_GENERATORS.contains(&configured.generator.as_str())
+                        && self.os_manager().find_executable(&plugin_name).is_none()
+                    {
+                        missing_plugins.push(plugin_name);
+                    }
+
+                    outputs.push(configured);
+                }
+            }
+        }
+
+        if !missing_plugins.is_empty() {
+            return Err(format!(
+                "Failed to check installation of the following dependencies: {}",
+                missing_plugins.join(",")
+            ));
         }
 
-        return Ok(result);
+        Ok(outputs)
     }
     /// Verifies that all required dependencies are installed and accessible.
     ///
     /// This function checks each dependency by running it with a version flag.
-    /// Dependencies are checked with a short timeout since version checks should be fast.
+    /// Dependencies are checked with a timeout, failing if a version check hangs.
     ///
     /// # Arguments
     ///
@@ -126,18 +363,18 @@ pub trait ProtobufCompiler: CompilerProperties {
     /// `dependency version_flag` and checking if it succeeds.
     fn ensure_dependencies(
         &self,
-        deps: &Vec<&'static str>,
-        version_flags: &Vec<&'static str>,
+        deps: &[&'static str],
+        version_flags: &[&'static str],
     ) -> Result<(), Vec<&'static str>> {
         let mut failed_commands: Vec<&'static str> = Vec::new();
 
         for i in 0..deps.len() {
-            if let Err(_) = self.os_manager().execute_command(
-                &deps[i],
-                &vec![String::from(version_flags[i])],
-                true,
-            ) {
-                failed_commands.push(&deps[i]);
+            if self
+                .os_manager()
+                .execute_command(deps[i], &[String::from(version_flags[i])], true, None)
+                .is_err()
+            {
+                failed_commands.push(deps[i]);
             }
         }
 
@@ -152,7 +389,8 @@ pub trait ProtobufCompiler: CompilerProperties {
     ///
     /// This function orchestrates the entire compilation process:
     /// 1. Checks that all dependencies are installed
-    /// 2. Assembles the protoc command with appropriate flags
+    /// 2. Assembles the protoc command with appropriate flags, keeping the
+    ///    order of `compile_options` so command lines are reproducible
     /// 3. Handles plugin configuration if needed
     /// 4. Executes the compilation
     ///
@@ -161,9 +399,9 @@ pub trait ProtobufCompiler: CompilerProperties {
     /// * `project` - Project configuration with proto files and options
     /// * `compiler_deps` - List of required dependencies for this language
     /// * `compiler_version_flags` - Version flags for dependency checking
-    /// * `compiler_out_flag` - Protoc flag for message output (e.g., "--go_out", "--csharp_out")
-    /// * `compiler_out_grpc_flag` - Protoc flag for gRPC output (e.g., "--go-grpc_out", "--grpc_out")
-    /// * `compiler_plugin` - Optional plugin name if a custom gRPC plugin is needed
+    /// * `compiler_outputs` - Protoc outputs to generate (e.g., `go` and `go-grpc`), each one
+    ///   becoming `--<generator>_out`, an optional `--<generator>_opt` and an optional `--plugin`.
+    ///   The project's `generators` are merged in with [`ProtobufCompiler::merge_outputs`]
     ///
     /// # Returns
     ///
@@ -175,16 +413,13 @@ pub trait ProtobufCompiler: CompilerProperties {
     /// Returns an error if:
     /// - Required dependencies are missing
     /// - Output flags are specified in compile_options (they're handled automatically)
-    /// - Plugin is required but plugin_path is not provided
     /// - Protoc command execution fails
     fn assemble_compilation(
         &self,
         project: &Project,
         compiler_deps: Vec<&'static str>,
         compiler_version_flags: Vec<&'static str>,
-        compiler_out_flag: &'static str,
-        compiler_out_grpc_flag: &'static str,
-        compiler_plugin: Option<&'static str>,
+        compiler_outputs: Vec<ProtocOutput>,
     ) -> Result<(), String> {
         // Check that all required dependencies are installed
         if let Err(failed_dependencies) =
@@ -197,13 +432,32 @@ pub trait ProtobufCompiler: CompilerProperties {
             return Err(error_msg);
         }
 
-        // Build the protoc command arguments
-        let mut command_args: Vec<String> = Vec::new();
+        let compiler_outputs: Vec<ProtocOutput> = self.merge_outputs(project, compiler_outputs)?;
+
+        // Build the protoc command arguments, starting with any prefix required
+        // by the protoc invocation itself (e.g., `-m grpc_tools.protoc`)
+        let protoc_command = self.protoc_command();
+        let mut command_args: Vec<String> = protoc_command[1..]
+            .iter()
+            .map(|arg| arg.to_string())
+            .collect();
+
+        let out_flags: Vec<String> = compiler_outputs
+            .iter()
+            .map(|output| output.out_flag())
+            .collect();
+
+        // Add the include paths first, so they take precedence over the well-known types
+        let include_paths: Vec<&String> = project.include_paths.iter().flatten().collect();
+        for include_path in include_paths.iter() {
+            command_args.push(format!("{}={}", PROTO_PATH_FLAG, include_path));
+        }
 
-        // Add custom compile options from the project configuration
+        // Add custom compile options from the project configuration, in the
+        // order they were written (flags such as -I may be repeated)
         for (key, value) in project.compile_options.iter() {
             // Prevent users from specifying output flags manually (we handle them)
-            if key == compiler_out_flag || key == compiler_out_grpc_flag {
+            if out_flags.contains(key) {
                 let err = format!(
                     "The argument {} do not must appear. Protoweld handle this using compiled_proto_folder option",
                     key
@@ -211,51 +465,108 @@ pub trait ProtobufCompiler: CompilerProperties {
                 return Err(err);
             }
 
-            // Format flags: empty value means flag-only (e.g., "--include_imports"),
-            // non-empty means key=value (e.g., "-I=entities")
-            let flag = match value == "" {
-                true => format!("{}", key),
-                false => format!("{}={}", key, value),
-            };
-            command_args.push(flag);
+            command_args.push(protoc_flag(key, value));
         }
 
-        // Add output flags for messages and gRPC services
-        command_args.push(format!(
-            "{}={}",
-            &compiler_out_flag, &project.compiled_proto_folder,
-        ));
-
-        command_args.push(format!(
-            "{}={}",
-            &compiler_out_grpc_flag, &project.compiled_proto_folder,
-        ));
-
-        // Add plugin configuration if needed (e.g., for .NET)
-        if let Some(plugin) = compiler_plugin {
-            if let Some(plugin_path) = &project.plugin_path {
-                let plugin_flag = format!("--plugin=protoc-gen-grpc={}", plugin_path);
-                command_args.push(plugin_flag);
-            } else {
-                return Err(format!(
-                    "The plugin {} must have a path in plugin_path option",
-                    plugin
+        // Add the well-known types last, so project files shadowing them still win
+        if let Some(well_known_types) = self.well_known_types_include() {
+            // Without any --proto_path protoc searches the current folder, which
+            // must be kept once the well-known types folder is added
+            let has_include_paths = !include_paths.is_empty()
+                || project.compile_options.iter().any(|(key, _)| {
+                    key == PROTO_PATH_FLAG || key == PROTO_PATH_SHORT_FLAG
+                });
+            if !has_include_paths {
+                command_args.push(format!("{}=.", PROTO_PATH_FLAG));
+            }
+
+            debug!("Using well-known types from {}", well_known_types.display());
+            command_args.push(format!(
+                "{}={}",
+                PROTO_PATH_FLAG,
+                well_known_types.to_string_lossy()
+            ));
+        }
+
+        command_args.extend(self.extra_protoc_arguments(project));
+
+        // Add output flags, generator parameters and plugin locations
+        for output in compiler_outputs.iter() {
+            // protoc refuses to write into missing folders
+            self.os_manager()
+                .create_folder(&PathBuf::from(output.folder(project)))?;
+
+            command_args.push(format!(
+                "{}={}",
+                output.out_flag(),
+                output.folder(project),
+            ));
+
+            if !output.options.is_empty() {
+                command_args.push(format!(
+                    "--{}_opt={}",
+                    output.generator,
+                    output.options.join(",")
+                ));
+            }
+
+            if let Some(plugin_path) = &output.plugin_path {
+                command_args.push(format!(
+                    "--plugin=protoc-gen-{}={}",
+                    output.generator, plugin_path
                 ));
             }
         }
 
-        // Add all proto files to compile
+        // Add all proto files to compile, relative to the folder protoc runs in
+        // so their names inside protoc don't depend on where the project lives
         for proto in project.associated_proto_files.iter() {
-            command_args.push(String::from(proto));
+            command_args.push(
+                relative_path(&project.base_dir, &PathBuf::from(proto))
+                    .to_string_lossy()
+                    .to_string(),
+            );
         }
 
-        // Execute the protoc command
-        if let Err(err) = self
-            .os_manager()
-            .execute_command("protoc", &command_args, false)
-        {
-            return Err(err);
-        }
-        Ok(())
+        // Execute the protoc command from the project's base folder, where
+        // include paths and compile options are resolved
+        self.os_manager().execute_command(
+            protoc_command[0],
+            &command_args,
+            false,
+            Some(&project.base_dir),
+        )
+    }
+}
+
+/// Formats a `compile_options` entry as a protoc argument.
+///
+/// An empty value means a flag without value (e.g., `--include_imports`),
+/// short flags take their value attached (e.g., `-Ientities`) and long flags
+/// use `flag=value` (e.g., `--proto_path=entities`).
+fn protoc_flag(flag: &str, value: &str) -> String {
+    match (value.is_empty(), flag.starts_with("--")) {
+        (true, _) => flag.to_string(),
+        (false, false) => format!("{}{}", flag, value),
+        (false, true) => format!("{}={}", flag, value),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn formats_compile_options_as_protoc_flags() {
+        assert_eq!(protoc_flag("-I", "entities"), "-Ientities");
+        assert_eq!(
+            protoc_flag("--proto_path", "entities"),
+            "--proto_path=entities"
+        );
+        assert_eq!(
+            protoc_flag("--experimental_allow_proto3_optional", ""),
+            "--experimental_allow_proto3_optional"
+        );
+        assert_eq!(protoc_flag("-I", ""), "-I");
     }
 }
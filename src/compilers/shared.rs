@@ -1,13 +1,18 @@
-//! Factory function for creating language-specific compilers.
+//! Factory function for creating language-specific compilers, along with
+//! helpers shared by their post-processing steps.
 
-use std::{fs, path::PathBuf};
+use std::{
+    cmp::Ordering,
+    env, fs,
+    path::{Component, Path, PathBuf},
+};
 
-use log::error;
+use log::{error, warn};
 
 use crate::{
     compilers::{langs_compilers::compiler_types::CompilerParams, protobuf_compiler::ProtobufCompiler},
     os::shared::get_os_manager,
-    parser::types::Lang,
+    parser::types::{GrpcServices, Lang, Project},
 };
 
 /// Creates an appropriate compiler for the specified language.
@@ -57,5 +62,148 @@ pub fn get_compiler(
         os_manager: platform_manager,
     };
 
-    return Ok(params.into());
+    Ok(params.into())
+}
+
+/// Translates the project's `grpc_services` into options of a gRPC generator.
+///
+/// # Arguments
+///
+/// * `project` - Project whose `grpc_services` setting is translated
+/// * `generator` - Name of the gRPC generator (used for warnings)
+/// * `client_options` - Options restricting the output to clients, `None` if unsupported
+/// * `server_options` - Options restricting the output to servers, `None` if unsupported
+///
+/// # Returns
+///
+/// The options to pass to the generator. When the plugin can't restrict its
+/// output, a warning is logged and both sides are generated.
+pub fn grpc_services_options(
+    project: &Project,
+    generator: &str,
+    client_options: Option<&[&'static str]>,
+    server_options: Option<&[&'static str]>,
+) -> Vec<String> {
+    let options = match project.grpc_services {
+        GrpcServices::Client => client_options,
+        GrpcServices::Server => server_options,
+        GrpcServices::Both | GrpcServices::None => Some(&[][..]),
+    };
+
+    match options {
+        Some(options) => options.iter().map(|option| option.to_string()).collect(),
+        None => {
+            warn!(
+                "Generator {} of project {} can't restrict gRPC services to {:?}, generating both client and server code",
+                generator, project.path, project.grpc_services
+            );
+            Vec::new()
+        }
+    }
+}
+
+/// Computes the path of `target` relative to the directory `from_dir`.
+///
+/// Both paths are resolved against the current directory and normalized
+/// lexically, so they don't need to exist yet. This lets post-processing
+/// steps reference files generated in another output folder.
+///
+/// # Example
+///
+/// From `out/contracts` to `out/server/users.grpc.pb.h` the result is
+/// `../server/users.grpc.pb.h`.
+pub fn relative_path(from_dir: &Path, target: &Path) -> PathBuf {
+    let from_parts = normalized_components(from_dir);
+    let target_parts = normalized_components(target);
+
+    let common = from_parts
+        .iter()
+        .zip(target_parts.iter())
+        .take_while(|(from, target)| from == target)
+        .count();
+
+    let mut result: PathBuf = PathBuf::new();
+    for _ in common..from_parts.len() {
+        result.push(Component::ParentDir);
+    }
+    result.extend(target_parts[common..].iter());
+
+    result
+}
+
+/// Returns the components of an absolute, lexically normalized version of `path`.
+fn normalized_components(path: &Path) -> Vec<String> {
+    let absolute: PathBuf = match path.is_absolute() {
+        true => path.to_path_buf(),
+        false => env::current_dir().unwrap_or_default().join(path),
+    };
+
+    let mut parts: Vec<String> = Vec::new();
+    for component in absolute.components() {
+        match component {
+            Component::Normal(name) => parts.push(name.to_string_lossy().to_string()),
+            Component::ParentDir => {
+                parts.pop();
+            }
+            _ => {}
+        }
+    }
+
+    parts
+}
+
+/// Returns the name protoc gives to a proto file: its path relative to the
+/// first include root containing it (e.g., `security/auth.proto`).
+///
+/// Generators name their output after it, so `security/auth.proto` becomes
+/// `security/auth.pb.cc` in C++.
+pub fn proto_import_name(proto: &String, include_roots: &[PathBuf]) -> PathBuf {
+    let proto_path: PathBuf = PathBuf::from(proto);
+    include_roots
+        .iter()
+        .map(|root| relative_path(root, &proto_path))
+        .find(|name| !matches!(name.components().next(), Some(Component::ParentDir)))
+        .unwrap_or(proto_path)
+}
+
+/// Compares two package versions, such as NuGet ones (e.g., `2.72.0` and
+/// `2.73.0-pre1`) or Go module ones (e.g., `v1.64.0`).
+///
+/// Numeric components are compared in order, missing ones counting as zeros,
+/// and a prerelease sorts before the release with the same numbers.
+pub fn compare_versions(left: &str, right: &str) -> Ordering {
+    let parse = |version: &str| -> (Vec<u64>, bool) {
+        let version: &str = version.trim_start_matches('v');
+        let (numbers, prerelease) = match version.split_once('-') {
+            Some((numbers, _)) => (numbers, true),
+            None => (version, false),
+        };
+        let mut numbers: Vec<u64> = numbers
+            .split('.')
+            .map(|number| number.parse::<u64>().unwrap_or(0))
+            .collect();
+        // Missing components are zeros (`2.72` is `2.72.0`)
+        while numbers.last() == Some(&0) {
+            numbers.pop();
+        }
+        (numbers, !prerelease)
+    };
+
+    parse(left).cmp(&parse(right))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn compares_versions() {
+        assert_eq!(compare_versions("2.72.0", "2.72.0"), Ordering::Equal);
+        assert_eq!(compare_versions("2.100.0", "2.72.0"), Ordering::Greater);
+        assert_eq!(compare_versions("2.9.0", "2.72.0"), Ordering::Less);
+        assert_eq!(compare_versions("2.72.0-pre1", "2.72.0"), Ordering::Less);
+        assert_eq!(compare_versions("2.73.0-pre1", "2.72.0"), Ordering::Greater);
+        assert_eq!(compare_versions("2.72", "2.72.0"), Ordering::Equal);
+        assert_eq!(compare_versions("v1.65.0", "v1.64.0"), Ordering::Greater);
+    }
 }
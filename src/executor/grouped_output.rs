@@ -0,0 +1,111 @@
+//! Log output grouped per project.
+//!
+//! Projects compiled at the same time would interleave their log lines, so
+//! the logger writes through [`GroupedOutput`], which keeps the lines logged
+//! inside [`capture`] in a per-thread buffer instead of printing them right away.
+
+use std::{
+    cell::RefCell,
+    io::{self, Write},
+    panic::{self, PanicHookInfo},
+    thread,
+};
+
+thread_local! {
+    /// Buffer of the lines logged by the current thread, while capturing.
+    static CAPTURED_OUTPUT: RefCell<Option<Vec<u8>>> = const { RefCell::new(None) };
+}
+
+/// Log target writing to stderr, or to the buffer of the current thread while
+/// [`capture`] runs.
+pub struct GroupedOutput;
+
+impl Write for GroupedOutput {
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        CAPTURED_OUTPUT.with(|captured| match captured.borrow_mut().as_mut() {
+            Some(output) => {
+                output.extend_from_slice(buf);
+                Ok(buf.len())
+            }
+            None => io::stderr().write(buf),
+        })
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        io::stderr().flush()
+    }
+}
+
+/// Runs `task`, returning its result together with everything it logged.
+///
+/// The captured output is meant to be printed in one piece with [`print_output`].
+pub fn capture<T>(task: impl FnOnce() -> T) -> (T, Vec<u8>) {
+    CAPTURED_OUTPUT.with(|captured| *captured.borrow_mut() = Some(Vec::new()));
+    let result = task();
+    let output = CAPTURED_OUTPUT.with(|captured| captured.borrow_mut().take());
+
+    (result, output.unwrap_or_default())
+}
+
+/// Panic hook of the process, as returned by [`panic::take_hook`].
+type PanicHook = Box<dyn Fn(&PanicHookInfo<'_>) + Sync + Send + 'static>;
+
+/// Panic hook writing panic messages through [`GroupedOutput`], so a panic
+/// inside [`capture`] is grouped with the lines logged by the same task.
+///
+/// The previous hook is restored when this is dropped.
+pub struct GroupedPanicHook {
+    /// Hook installed before this one
+    previous: Option<PanicHook>,
+}
+
+impl GroupedPanicHook {
+    /// Replaces the panic hook of the process until the returned value is dropped.
+    pub fn install() -> Self {
+        let previous: PanicHook = panic::take_hook();
+        panic::set_hook(Box::new(|info| {
+            let _ = writeln!(
+                GroupedOutput,
+                "thread '{}' {}",
+                thread::current().name().unwrap_or("<unnamed>"),
+                info
+            );
+        }));
+
+        GroupedPanicHook {
+            previous: Some(previous),
+        }
+    }
+}
+
+impl Drop for GroupedPanicHook {
+    fn drop(&mut self) {
+        if let Some(previous) = self.previous.take() {
+            panic::set_hook(previous);
+        }
+    }
+}
+
+/// Prints output returned by [`capture`] to stderr, without interleaving it
+/// with other lines.
+pub fn print_output(output: &[u8]) {
+    let mut stderr = io::stderr().lock();
+    let _ = stderr.write_all(output);
+    let _ = stderr.flush();
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn captures_panic_messages() {
+        let panic_hook: GroupedPanicHook = GroupedPanicHook::install();
+        let (result, output) = capture(|| panic::catch_unwind(|| panic!("protoc exploded")));
+        drop(panic_hook);
+
+        assert!(result.is_err());
+        assert!(String::from_utf8_lossy(&output).contains("panicked at"));
+        assert!(String::from_utf8_lossy(&output).contains("protoc exploded"));
+    }
+}
@@ -4,4 +4,5 @@
 //! in the configuration, selecting the appropriate compiler for each language
 //! and managing the overall generation workflow.
 
+pub mod grouped_output;
 pub mod protoweld_executor;
@@ -1,35 +1,124 @@
 //! Main executor for generating proto files across all configured projects.
 
+use std::{
+    panic::{self, AssertUnwindSafe},
+    path::PathBuf,
+    sync::{Condvar, Mutex},
+    thread,
+};
+
 use log::info;
 
 use crate::{
     compilers::{protobuf_compiler::ProtobufCompiler, shared::get_compiler},
-    parser::types::ProtoweldParser,
+    executor::grouped_output::{capture, print_output, GroupedPanicHook},
+    parser::types::{Project, ProtoweldParser},
 };
 
+/// State of a project during [`generate_protos`].
+#[derive(Clone, Copy, PartialEq)]
+enum ProjectStatus {
+    Pending,
+    Running,
+    Done,
+}
+
+/// Outcome of a compiled project.
+struct FinishedProject {
+    /// Result of the compilation
+    result: Result<(), String>,
+    /// Log output captured while compiling (empty when it was printed right away)
+    output: Vec<u8>,
+}
+
+/// Progress shared by the workers of [`generate_protos`].
+struct Schedule {
+    /// Status of each project, in configuration order
+    statuses: Vec<ProjectStatus>,
+    /// Outcome of each finished project
+    finished: Vec<Option<FinishedProject>>,
+    /// Number of leading projects whose output was already printed
+    printed: usize,
+    /// Whether a project failed, so no more projects are started unless
+    /// `keep_going` is set
+    failed: bool,
+}
+
+impl Schedule {
+    /// Returns the first pending project whose blocking projects are all done.
+    fn next_project(&self, blockers: &[Vec<usize>]) -> Option<usize> {
+        (0..self.statuses.len()).find(|&index| {
+            self.statuses[index] == ProjectStatus::Pending
+                && blockers[index]
+                    .iter()
+                    .all(|&blocker| self.statuses[blocker] == ProjectStatus::Done)
+        })
+    }
+
+    /// Prints the output of the finished projects that follow the ones
+    /// already printed, so logs appear in configuration order.
+    fn print_finished(&mut self) {
+        while let Some(Some(project)) = self.finished.get(self.printed) {
+            print_output(&project.output);
+            self.printed += 1;
+        }
+    }
+}
+
+/// Outcome of a project, as shown in the summary of [`generate_protos`].
+#[derive(Debug)]
+pub enum ProjectReport {
+    /// The project compiled successfully
+    Compiled,
+    /// The project failed, with the error (e.g., the `protoc` stderr)
+    Failed(String),
+    /// The project was not started because an earlier one failed
+    Skipped,
+}
+
+impl ProjectReport {
+    /// Label of the outcome in the summary table.
+    fn label(&self) -> &'static str {
+        match self {
+            ProjectReport::Compiled => "compiled",
+            ProjectReport::Failed(_) => "failed",
+            ProjectReport::Skipped => "skipped",
+        }
+    }
+}
+
 /// Generates proto files for all projects defined in the configuration.
 ///
-/// This function iterates through all active projects in the configuration,
-/// selects the appropriate compiler based on the target language, and
-/// compiles all associated proto files for each project.
+/// This function runs up to `jobs` projects at the same time, selects the
+/// appropriate compiler for each one based on its target language, and
+/// compiles all associated proto files of the project.
 ///
 /// # Arguments
 ///
 /// * `parser` - The parsed Protoweld configuration containing all project definitions
 /// * `base_path` - The base path of the configuration file (used for resolving relative paths)
+/// * `jobs` - Maximum number of projects compiled at the same time
+/// * `keep_going` - Compile every project even after a failure
 ///
 /// # Returns
 ///
 /// * `Ok(())` - All projects compiled successfully
-/// * `Err(String)` - Error message if compilation fails for any project
+/// * `Err(String)` - Error message of the first project, in configuration
+///   order, whose compilation failed, or the number of failed projects with
+///   `keep_going`
 ///
 /// # Process
 ///
-/// 1. Iterates through each project in `active_projects`
-/// 2. Gets the appropriate compiler for the project's language
-/// 3. Compiles all proto files for the project
-/// 4. Logs success for each compiled project
-/// 5. Returns early on first error
+/// 1. Starts `jobs` workers, which take the projects in configuration order
+/// 2. A project whose output folders overlap those of an earlier project waits
+///    for it to finish, so they never write to the same files at once
+/// 3. Each worker gets the compiler for the project's language and compiles it
+/// 4. With more than one worker, the log lines and panic messages of each
+///    project are grouped and printed in configuration order. A single worker
+///    prints them right away
+/// 5. No new project is started after a failure, unless `keep_going` is set
+/// 6. With `keep_going`, a summary table with the outcome of every project and
+///    the errors of the failed ones is printed
 ///
 /// # Errors
 ///
@@ -38,21 +127,305 @@ use crate::{
 /// - Required dependencies are missing
 /// - Proto file compilation fails
 /// - File system operations fail
-pub fn generate_protos(parser: &ProtoweldParser, base_path: &String) -> Result<(), String> {
-    for project in parser.active_projects.iter() {
-        let compiler_result = get_compiler(&project.lang, base_path);
-        if let Err(_) = compiler_result {
-            return Err(String::from("[PROTOWELD] Lang parsed do not supported."));
+pub fn generate_protos(
+    parser: &ProtoweldParser,
+    base_path: &String,
+    jobs: usize,
+    keep_going: bool,
+) -> Result<(), String> {
+    let projects: &Vec<Project> = &parser.active_projects;
+    let output_folders: Vec<Vec<PathBuf>> = projects.iter().map(Project::output_folders).collect();
+
+    // Earlier projects writing to the same folders, which must finish first
+    let blockers: Vec<Vec<usize>> = (0..projects.len())
+        .map(|index| {
+            (0..index)
+                .filter(|&earlier| {
+                    folders_overlap(&output_folders[index], &output_folders[earlier])
+                })
+                .collect()
+        })
+        .collect();
+
+    let schedule = Mutex::new(Schedule {
+        statuses: vec![ProjectStatus::Pending; projects.len()],
+        finished: projects.iter().map(|_| None).collect(),
+        printed: 0,
+        failed: false,
+    });
+    let project_done = Condvar::new();
+    let workers: usize = jobs.clamp(1, projects.len().max(1));
+
+    // Panic messages are grouped with the logs of their project as well
+    let panic_hook: Option<GroupedPanicHook> = (workers > 1).then(GroupedPanicHook::install);
+
+    thread::scope(|scope| {
+        for _ in 0..workers {
+            scope.spawn(|| loop {
+                let index: usize = {
+                    let mut state = schedule.lock().unwrap();
+                    loop {
+                        if (state.failed && !keep_going)
+                            || !state.statuses.contains(&ProjectStatus::Pending)
+                        {
+                            return;
+                        }
+                        if let Some(index) = state.next_project(&blockers) {
+                            state.statuses[index] = ProjectStatus::Running;
+                            break index;
+                        }
+                        state = project_done.wait(state).unwrap();
+                    }
+                };
+
+                let compile = || compile_guarded(&projects[index], base_path);
+                let (result, output) = match workers > 1 {
+                    true => capture(compile),
+                    false => (compile(), Vec::new()),
+                };
+
+                let mut state = schedule.lock().unwrap();
+                state.failed |= result.is_err();
+                state.statuses[index] = ProjectStatus::Done;
+                state.finished[index] = Some(FinishedProject { result, output });
+                state.print_finished();
+                project_done.notify_all();
+            });
         }
+    });
 
-        let compiler: Box<dyn ProtobufCompiler> = compiler_result.unwrap();
-        let compilation_result = compiler.compile_project(&project);
+    drop(panic_hook);
+    let state = schedule.into_inner().unwrap();
 
-        if let Err(error) = compilation_result {
-            return Err(error);
+    let mut reports: Vec<ProjectReport> = Vec::new();
+    for (index, project) in state.finished.into_iter().enumerate() {
+        let Some(project) = project else {
+            reports.push(ProjectReport::Skipped);
+            continue;
+        };
+        // After a failure, projects following a skipped one were not printed yet
+        if index >= state.printed {
+            print_output(&project.output);
         }
+        reports.push(match project.result {
+            Ok(()) => ProjectReport::Compiled,
+            Err(error) => ProjectReport::Failed(error),
+        });
+    }
 
-        info!("Compiled project {}", project.path);
+    if keep_going {
+        print_output(generation_summary(projects, &reports).as_bytes());
     }
+
+    let mut failures = reports.into_iter().filter_map(|report| match report {
+        ProjectReport::Failed(error) => Some(error),
+        _ => None,
+    });
+
+    match keep_going {
+        false => match failures.next() {
+            Some(error) => Err(error),
+            None => Ok(()),
+        },
+        true => match failures.count() {
+            0 => Ok(()),
+            failed => Err(format!("{} of {} projects failed.", failed, projects.len())),
+        },
+    }
+}
+
+/// Formats the outcome of every project as a table, followed by the errors of
+/// the failed projects.
+///
+/// For example:
+///
+/// ```text
+/// PROJECT           LANG    RESULT
+/// services/billing  GoLang  compiled
+/// services/auth     DotNet  failed
+///
+/// services/auth failed:
+///   auth.proto:3:1: Expected "package".
+/// ```
+fn generation_summary(projects: &[Project], reports: &[ProjectReport]) -> String {
+    let rows: Vec<[String; 3]> = projects
+        .iter()
+        .zip(reports)
+        .map(|(project, report)| {
+            [
+                project.path.clone(),
+                format!("{:?}", project.lang),
+                String::from(report.label()),
+            ]
+        })
+        .collect();
+
+    let header: [String; 3] = [
+        String::from("PROJECT"),
+        String::from("LANG"),
+        String::from("RESULT"),
+    ];
+    let widths: Vec<usize> = (0..2)
+        .map(|column| {
+            rows.iter()
+                .chain([&header])
+                .map(|row| row[column].len())
+                .max()
+                .unwrap_or(0)
+        })
+        .collect();
+
+    let mut summary: String = String::from("\n");
+    for row in [&header].into_iter().chain(rows.iter()) {
+        summary += &format!(
+            "{:<project$}  {:<lang$}  {}\n",
+            row[0],
+            row[1],
+            row[2],
+            project = widths[0],
+            lang = widths[1]
+        );
+    }
+
+    for (project, report) in projects.iter().zip(reports) {
+        if let ProjectReport::Failed(error) = report {
+            summary += &format!("\n{} failed:\n", project.path);
+            for line in error.trim_end().lines() {
+                summary += &format!("  {}\n", line);
+            }
+        }
+    }
+
+    summary
+}
+
+/// Compiles a single project with the compiler of its language.
+fn compile_project(project: &Project, base_path: &String) -> Result<(), String> {
+    let compiler_result = get_compiler(&project.lang, base_path);
+    if compiler_result.is_err() {
+        return Err(String::from("[PROTOWELD] Lang parsed do not supported."));
+    }
+
+    let compiler: Box<dyn ProtobufCompiler> = compiler_result.unwrap();
+    compiler.compile_project(project)?;
+
+    info!("Compiled project {}", project.path);
     Ok(())
 }
+
+/// Compiles a project like [`compile_project`], turning a panic into an error
+/// so the project is still marked as done and other workers don't wait for it.
+fn compile_guarded(project: &Project, base_path: &String) -> Result<(), String> {
+    match panic::catch_unwind(AssertUnwindSafe(|| compile_project(project, base_path))) {
+        Ok(result) => result,
+        Err(payload) => {
+            let message: String = match payload.downcast_ref::<&str>() {
+                Some(message) => message.to_string(),
+                None => payload
+                    .downcast_ref::<String>()
+                    .cloned()
+                    .unwrap_or_default(),
+            };
+            Err(format!(
+                "[PROTOWELD] The compilation of {} panicked: {}",
+                project.path, message
+            ))
+        }
+    }
+}
+
+/// Whether two projects write to the same folder, or one inside the other.
+fn folders_overlap(left: &[PathBuf], right: &[PathBuf]) -> bool {
+    left.iter().any(|left_folder| {
+        right.iter().any(|right_folder| {
+            left_folder.starts_with(right_folder) || right_folder.starts_with(left_folder)
+        })
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn folders(paths: &[&str]) -> Vec<PathBuf> {
+        paths.iter().map(PathBuf::from).collect()
+    }
+
+    fn project(path: &str, lang: &str) -> Project {
+        serde_yaml::from_str(&format!(
+            "path: {}\nlang: {}\ncompiled_proto_folder: out\nassociated_proto_files: []\n",
+            path, lang
+        ))
+        .unwrap()
+    }
+
+    #[test]
+    fn overlaps_same_and_nested_folders() {
+        assert!(folders_overlap(
+            &folders(&["out/go"]),
+            &folders(&["out/go"])
+        ));
+        assert!(folders_overlap(&folders(&["out"]), &folders(&["out/go"])));
+        assert!(folders_overlap(
+            &folders(&["out/go/grpc"]),
+            &folders(&["out/go"])
+        ));
+        assert!(folders_overlap(
+            &folders(&["out/messages", "out/grpc"]),
+            &folders(&["out/cs", "out/grpc"])
+        ));
+    }
+
+    #[test]
+    fn does_not_overlap_sibling_folders() {
+        assert!(!folders_overlap(
+            &folders(&["out/go"]),
+            &folders(&["out/gopher"])
+        ));
+        assert!(!folders_overlap(
+            &folders(&["out/go", "out/go-grpc"]),
+            &folders(&["out/cs"])
+        ));
+        assert!(!folders_overlap(&folders(&[]), &folders(&["out"])));
+    }
+
+    #[test]
+    fn summarizes_outcomes_in_aligned_columns() {
+        let projects: Vec<Project> = vec![
+            project("services/billing", "GoLang"),
+            project("auth", "DotNet"),
+            project("web-frontend", "TypeScript"),
+        ];
+        let reports: Vec<ProjectReport> = vec![
+            ProjectReport::Compiled,
+            ProjectReport::Failed(String::from(
+                "protos/auth.proto:12:3: \"Token\" is not defined.\nprotos/auth.proto:14:3: \"Role\" is not defined.\n",
+            )),
+            ProjectReport::Skipped,
+        ];
+
+        assert_eq!(
+            generation_summary(&projects, &reports),
+            "\n\
+             PROJECT           LANG        RESULT\n\
+             services/billing  GoLang      compiled\n\
+             auth              DotNet      failed\n\
+             web-frontend      TypeScript  skipped\n\
+             \n\
+             auth failed:\n  \
+             protos/auth.proto:12:3: \"Token\" is not defined.\n  \
+             protos/auth.proto:14:3: \"Role\" is not defined.\n"
+        );
+    }
+
+    #[test]
+    fn widens_columns_to_fit_the_header() {
+        let projects: Vec<Project> = vec![project("api", "Rust")];
+        let reports: Vec<ProjectReport> = vec![ProjectReport::Compiled];
+
+        assert_eq!(
+            generation_summary(&projects, &reports),
+            "\nPROJECT  LANG  RESULT\napi      Rust  compiled\n"
+        );
+    }
+}
@@ -5,3 +5,4 @@ pub mod parser;
 pub mod executor;
 pub mod compilers;
 pub mod os;
+pub mod proto;
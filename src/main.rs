@@ -3,9 +3,12 @@
 //! This module handles CLI argument parsing, configuration file loading,
 //! and orchestrates the proto file compilation process.
 
+use std::process;
+
 use clap::Parser;
+use log::error;
 use protoweld::{
-    executor::protoweld_executor::generate_protos,
+    executor::{grouped_output::GroupedOutput, protoweld_executor::generate_protos},
     parser::types::{IProtoweldParser, ProtoweldParser},
     types::cli::Cli,
 };
@@ -19,27 +22,35 @@ use protoweld::{
 /// 4. Generates proto files for all configured projects
 /// 5. Reports success or failure
 ///
-/// # Panics
+/// # Exit Code
 ///
-/// This function will panic if:
+/// The process logs the error and exits with code 1 if:
 /// - The configuration file cannot be read or parsed
 /// - Proto file generation fails for any project
 fn main() {
-    env_logger::init();
+    env_logger::Builder::from_default_env()
+        .target(env_logger::Target::Pipe(Box::new(GroupedOutput)))
+        .init();
 
     let args = Cli::parse();
 
-    let result = ProtoweldParser::parse(&args.filename);
-
-    if let Err(error) = result {
-        panic!("[PROTOWELD] Parser throw a error: {}", error)
-    }
+    let parser = match ProtoweldParser::parse(&args.filename) {
+        Ok(parser) => parser,
+        Err(error) => {
+            error!("[PROTOWELD] Parser throw a error: {}", error);
+            process::exit(1)
+        }
+    };
 
-    let parser = result.unwrap();
-    let generation_result = generate_protos(&parser, &args.filename);
+    let jobs: usize = match args.jobs {
+        Some(jobs) => jobs as usize,
+        None => parser.jobs.unwrap_or(1),
+    };
+    let generation_result = generate_protos(&parser, &args.filename, jobs, args.keep_going);
 
     if let Err(error) = generation_result {
-        panic!("[PROTOWELD] Generation failed. {}", error)
+        error!("[PROTOWELD] Generation failed. {}", error);
+        process::exit(1)
     }
 
     println!("[PROTOWELD] Generation completed.")
@@ -1,6 +1,6 @@
 //! Operating system manager trait and platform-specific implementations.
 
-use std::path::PathBuf;
+use std::path::{Path, PathBuf};
 
 /// Trait for platform-specific system operations.
 ///
@@ -14,7 +14,8 @@ pub trait OSManager {
     ///
     /// * `command` - The command to execute (e.g., "protoc", "go")
     /// * `arguments` - Vector of command-line arguments
-    /// * `dependency` - If `true`, this is a dependency check command (shorter timeout)
+    /// * `dependency` - If `true`, this is a dependency check command (fails on timeout)
+    /// * `current_dir` - Working directory of the command (defaults to the current one)
     ///
     /// # Returns
     ///
@@ -23,33 +24,34 @@ pub trait OSManager {
     ///
     /// # Behavior
     ///
-    /// - Dependency checks use a shorter timeout (1 second)
-    /// - Non-dependency commands use a longer timeout (5 seconds)
+    /// - Dependency checks and non-dependency commands use a 5 second timeout
     /// - Commands that timeout are killed if they're dependency checks
     /// - Stderr is captured and returned in error messages
     fn execute_command(
         &self,
         command: &'static str,
-        arguments: &Vec<String>,
+        arguments: &[String],
         dependency: bool,
+        current_dir: Option<&PathBuf>,
     ) -> Result<(), String>;
 
-    /// Searches for a string pattern in a file and returns the content and position.
+    /// Executes a short-lived command and returns what it printed.
     ///
     /// # Arguments
     ///
-    /// * `file` - Path to the file to search
-    /// * `search` - String pattern to search for
+    /// * `command` - The command to execute (e.g., "protoc-gen-go")
+    /// * `arguments` - Vector of command-line arguments
     ///
     /// # Returns
     ///
-    /// * `Ok((String, usize))` - Tuple of (file content, byte position of first match)
-    /// * `Err(String)` - Error message if file cannot be read
+    /// * `Ok(String)` - Standard output of the command
+    /// * `Err(String)` - Error message if the command fails or times out
     ///
-    /// # Note
+    /// # Behavior
     ///
-    /// If the pattern is not found, the position will be `usize::MAX`.
-    fn grep(&self, file: PathBuf, search: &'static str) -> Result<(String, usize), String>;
+    /// Uses the same short timeout as dependency checks, so it's meant for
+    /// commands such as `--version` probes.
+    fn command_output(&self, command: &str, arguments: &[String]) -> Result<String, String>;
 
     /// Renames or moves a file from one path to another.
     ///
@@ -62,7 +64,7 @@ pub trait OSManager {
     ///
     /// * `Ok(())` - File renamed successfully
     /// * `Err(String)` - Error message if rename operation fails
-    fn rename_file(&self, old: &PathBuf, nw: &PathBuf) -> Result<(), String>;
+    fn rename_file(&self, old: &Path, nw: &Path) -> Result<(), String>;
 
     /// Replaces all occurrences of a pattern with a replacement string in a file.
     ///
@@ -76,7 +78,7 @@ pub trait OSManager {
     ///
     /// * `Ok(())` - File modified successfully
     /// * `Err(String)` - Error message if file operations fail
-    fn find_replace(&self, file: &PathBuf, pattern: String, replace: String) -> Result<(), String>;
+    fn find_replace(&self, file: &Path, pattern: String, replace: String) -> Result<(), String>;
 
     /// Inserts text at a specific byte position in a file.
     ///
@@ -94,7 +96,7 @@ pub trait OSManager {
     /// # Panics
     ///
     /// This function will return an error if `position` exceeds the file length.
-    fn insert_in_position(&self, file: &PathBuf, position: usize, text: String) -> Result<(), String>;
+    fn insert_in_position(&self, file: &Path, position: usize, text: String) -> Result<(), String>;
 
     /// Creates a new file with the specified content.
     ///
@@ -107,7 +109,112 @@ pub trait OSManager {
     ///
     /// * `Ok(())` - File created successfully
     /// * `Err(String)` - Error message if file creation or writing fails
-    fn write_new_file(&self, file: &PathBuf, text: String) -> Result<(), String>;
+    fn write_new_file(&self, file: &Path, text: String) -> Result<(), String>;
+
+    /// Reads the whole content of a file into a string.
+    ///
+    /// # Arguments
+    ///
+    /// * `file` - Path to the file to read
+    ///
+    /// # Returns
+    ///
+    /// * `Ok(String)` - File content
+    /// * `Err(String)` - Error message if the file cannot be read
+    fn read_file(&self, file: &Path) -> Result<String, String>;
+
+    /// Reads the whole content of a binary file.
+    ///
+    /// # Arguments
+    ///
+    /// * `file` - Path to the file to read
+    ///
+    /// # Returns
+    ///
+    /// * `Ok(Vec<u8>)` - File content
+    /// * `Err(String)` - Error message if the file cannot be read
+    fn read_bytes(&self, file: &Path) -> Result<Vec<u8>, String>;
+
+    /// Recursively lists every regular file below a directory.
+    ///
+    /// # Arguments
+    ///
+    /// * `dir` - Directory to walk
+    ///
+    /// # Returns
+    ///
+    /// * `Ok(Vec<PathBuf>)` - Paths of all files found, sorted for stable iteration
+    /// * `Err(String)` - Error message if the directory cannot be walked
+    fn list_files(&self, dir: &Path) -> Result<Vec<PathBuf>, String>;
+
+    /// Lists the folders directly inside a directory.
+    ///
+    /// # Arguments
+    ///
+    /// * `dir` - Directory to read
+    ///
+    /// # Returns
+    ///
+    /// * `Ok(Vec<PathBuf>)` - Paths of the folders found, sorted for stable iteration
+    /// * `Err(String)` - Error message if the directory cannot be read
+    fn list_folders(&self, dir: &Path) -> Result<Vec<PathBuf>, String>;
+
+    /// Creates a directory and all of its missing parents.
+    ///
+    /// # Arguments
+    ///
+    /// * `dir` - Directory to create
+    ///
+    /// # Returns
+    ///
+    /// * `Ok(())` - Directory exists after the call
+    /// * `Err(String)` - Error message if the directory cannot be created
+    fn create_folder(&self, dir: &Path) -> Result<(), String>;
+
+    /// Removes a file or a directory tree, succeeding if it doesn't exist.
+    ///
+    /// # Arguments
+    ///
+    /// * `path` - File or directory to remove
+    ///
+    /// # Returns
+    ///
+    /// * `Ok(())` - Nothing exists at `path` after the call
+    /// * `Err(String)` - Error message if the path cannot be removed
+    fn remove_path(&self, path: &Path) -> Result<(), String>;
+
+    /// Checks whether a path points to an existing regular file.
+    ///
+    /// # Arguments
+    ///
+    /// * `path` - Path to check
+    ///
+    /// # Returns
+    ///
+    /// * `true` - The path exists and is a file (symbolic links are followed)
+    /// * `false` - Nothing exists at `path`, it is a directory, or it can't be read
+    fn is_file(&self, path: &Path) -> bool;
+
+    /// Resolves an executable name against the directories in `PATH`.
+    ///
+    /// # Arguments
+    ///
+    /// * `command` - Executable name (e.g., "protoc", "grpc_cpp_plugin")
+    ///
+    /// # Returns
+    ///
+    /// * `Some(PathBuf)` - Path of the first matching executable
+    /// * `None` - The executable is not in `PATH`
+    fn find_executable(&self, command: &str) -> Option<PathBuf>;
+
+    /// Returns the folders of the NuGet global packages cache, in lookup order.
+    ///
+    /// # Returns
+    ///
+    /// * `Vec<PathBuf>` - `NUGET_PACKAGES` when set, then the default cache
+    ///   folder of the user (e.g., `~/.nuget/packages`). Folders are not
+    ///   checked for existence
+    fn nuget_packages_folders(&self) -> Vec<PathBuf>;
 }
 
 /// Unix-like system manager implementation (Linux, macOS, etc.)
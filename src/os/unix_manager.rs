@@ -4,63 +4,44 @@
 //! command execution, and text manipulation. It handles Linux and macOS systems.
 
 use std::{
-    fs,
+    env, fs,
     io::Write,
-    path::PathBuf,
+    os::unix::fs::PermissionsExt,
+    path::{Path, PathBuf},
     process::{Command, Stdio},
     time::Duration,
 };
 
 use log::info;
 use wait_timeout::ChildExt;
+use walkdir::WalkDir;
 
 use crate::os::types::{OSManager, UnixManager};
 
 /// Timeout duration in seconds for dependency check commands (e.g., `protoc --version`).
 ///
-/// Dependency checks should be fast, so a short timeout is used.
-static DEPENDENCY_COMMAND_TIMEOUT: u64 = 1;
+/// Dependency checks should be fast, but with several projects compiled at the
+/// same time they compete with other processes, so they get some margin.
+static DEPENDENCY_COMMAND_TIMEOUT: u64 = 5;
 
 /// Timeout duration in seconds for non-dependency commands (e.g., actual proto compilation).
 ///
 /// Compilation commands may take longer, so a longer timeout is used.
 static NON_DEPENDENCY_COMMAND_TIMEOUT: u64 = 5;
 
-impl OSManager for UnixManager {
-    /// Searches for a pattern in a file and returns the content with match position.
-    ///
-    /// This implementation reads the entire file into memory and searches for
-    /// the pattern using string matching. The position is returned as a byte offset.
-    ///
-    /// # Arguments
-    ///
-    /// * `file` - Path to the file to search
-    /// * `search` - String pattern to find
-    ///
-    /// # Returns
-    ///
-    /// * `Ok((String, usize))` - Tuple of (file content, byte position of first match)
-    /// * `Err(String)` - Error if file cannot be read
-    ///
-    /// # Note
-    ///
-    /// Returns `usize::MAX` as the position if the pattern is not found.
-    fn grep(&self, file: PathBuf, search: &'static str) -> Result<(String, usize), String> {
-        let content = fs::read_to_string(file);
-        if let Err(e) = content {
-            return Err(e.to_string());
-        }
+/// Environment variable listing the directories searched for executables.
+static PATH_VARIABLE: &str = "PATH";
 
-        let text = content.unwrap();
-        Ok((
-            text.clone(),
-            match text.find(&search) {
-                Some(index) => index.into(),
-                None => usize::MAX,
-            },
-        ))
-    }
+/// Environment variable overriding the NuGet global packages folder.
+static NUGET_PACKAGES_VARIABLE: &str = "NUGET_PACKAGES";
+
+/// Environment variable with the home folder of the user.
+static HOME_VARIABLE: &str = "HOME";
+
+/// Default NuGet global packages folder, relative to the home folder.
+static NUGET_DEFAULT_PACKAGES_FOLDER: &str = ".nuget/packages";
 
+impl OSManager for UnixManager {
     /// Executes a command with timeout handling and error capture.
     ///
     /// This implementation spawns a child process, captures stderr, and applies
@@ -70,7 +51,8 @@ impl OSManager for UnixManager {
     ///
     /// * `command` - The command to execute
     /// * `arguments` - Command-line arguments
-    /// * `dependency` - If true, uses shorter timeout and fails on timeout
+    /// * `dependency` - If true, fails on timeout
+    /// * `current_dir` - Working directory of the child process, if not the current one
     ///
     /// # Returns
     ///
@@ -79,17 +61,23 @@ impl OSManager for UnixManager {
     ///
     /// # Behavior
     ///
-    /// - Dependency checks: 1 second timeout, failure on timeout
+    /// - Dependency checks: 5 second timeout, failure on timeout
     /// - Compilation commands: 5 second timeout, success on timeout (some plugins hang)
     /// - Stderr is captured and returned in error messages
     /// - Commands that timeout are killed
     fn execute_command(
         &self,
         command: &'static str,
-        arguments: &Vec<String>,
+        arguments: &[String],
         dependency: bool,
+        current_dir: Option<&PathBuf>,
     ) -> Result<(), String> {
-        let child_result = Command::new(command)
+        let mut process = Command::new(command);
+        if let Some(current_dir) = current_dir {
+            process.current_dir(current_dir);
+        }
+
+        let child_result = process
             .args(arguments)
             .stdout(Stdio::null())
             .stdin(Stdio::null())
@@ -138,10 +126,50 @@ impl OSManager for UnixManager {
         }
     }
 
+    /// Runs the command with stdout and stderr captured, killing it after the
+    /// dependency check timeout.
+    fn command_output(&self, command: &str, arguments: &[String]) -> Result<String, String> {
+        let child_result = Command::new(command)
+            .args(arguments)
+            .stdout(Stdio::piped())
+            .stdin(Stdio::null())
+            .stderr(Stdio::piped())
+            .spawn();
+
+        if let Err(err) = child_result {
+            info!("Child spawn error: {}", err);
+            return Err(err.to_string());
+        }
+
+        let mut child = child_result.unwrap();
+        let time_out_duration = Duration::from_secs(DEPENDENCY_COMMAND_TIMEOUT);
+
+        match child.wait_timeout(time_out_duration) {
+            Ok(Some(status)) if status.success() => match child.stdout.take() {
+                Some(stdout) => std::io::read_to_string(stdout).map_err(|e| e.to_string()),
+                None => Ok(String::new()),
+            },
+            Ok(Some(status)) => match child.stderr.take() {
+                Some(stderr) => Err(std::io::read_to_string(stderr).unwrap_or_default()),
+                None => Err(format!("{} exited with status: {}", command, status)),
+            },
+            Ok(None) => {
+                let _ = child.kill();
+                let _ = child.wait();
+                Err(format!(
+                    "{} command timed out after {} seconds.",
+                    command,
+                    time_out_duration.as_secs()
+                ))
+            }
+            Err(e) => Err(e.to_string()),
+        }
+    }
+
     /// Renames a file using the filesystem rename operation.
     ///
     /// This is an atomic operation on Unix systems when both paths are on the same filesystem.
-    fn rename_file(&self, old: &PathBuf, nw: &PathBuf) -> Result<(), String> {
+    fn rename_file(&self, old: &Path, nw: &Path) -> Result<(), String> {
         let result = fs::rename(old, nw);
         if let Err(e) = result {
             Err(e.to_string())
@@ -154,7 +182,7 @@ impl OSManager for UnixManager {
     ///
     /// Reads the entire file, performs string replacement, and writes it back.
     /// This operation is not atomic - if writing fails, the file may be corrupted.
-    fn find_replace(&self, file: &PathBuf, pattern: String, replace: String) -> Result<(), String> {
+    fn find_replace(&self, file: &Path, pattern: String, replace: String) -> Result<(), String> {
         let content = fs::read_to_string(file);
         if let Err(e) = content {
             return Err(e.to_string());
@@ -177,12 +205,7 @@ impl OSManager for UnixManager {
     /// # Errors
     ///
     /// Returns an error if the position exceeds the file length.
-    fn insert_in_position(
-        &self,
-        file: &PathBuf,
-        position: usize,
-        text: String,
-    ) -> Result<(), String> {
+    fn insert_in_position(&self, file: &Path, position: usize, text: String) -> Result<(), String> {
         let content = fs::read_to_string(file);
         if let Err(e) = content {
             return Err(e.to_string());
@@ -192,10 +215,10 @@ impl OSManager for UnixManager {
         if position <= file_str.len() {
             file_str.insert_str(position, &text);
         } else {
-            return Err(String::from(format!(
+            return Err(format!(
                 "Position out of bounds. File lenght {} characters",
                 file_str.len()
-            )));
+            ));
         }
 
         let result = fs::write(file, file_str);
@@ -209,7 +232,7 @@ impl OSManager for UnixManager {
     /// Creates a new file and writes content to it.
     ///
     /// This will overwrite any existing file at the specified path.
-    fn write_new_file(&self, file: &PathBuf, text: String) -> Result<(), String> {
+    fn write_new_file(&self, file: &Path, text: String) -> Result<(), String> {
         let file_result = fs::File::create(file);
         if let Err(e) = file_result {
             return Err(e.to_string());
@@ -220,4 +243,123 @@ impl OSManager for UnixManager {
             Ok(_) => Ok(()),
         }
     }
+
+    /// Reads a file into memory as UTF-8 text.
+    fn read_file(&self, file: &Path) -> Result<String, String> {
+        match fs::read_to_string(file) {
+            Err(e) => Err(e.to_string()),
+            Ok(content) => Ok(content),
+        }
+    }
+
+    /// Reads a file into memory without decoding it.
+    fn read_bytes(&self, file: &Path) -> Result<Vec<u8>, String> {
+        match fs::read(file) {
+            Err(e) => Err(e.to_string()),
+            Ok(content) => Ok(content),
+        }
+    }
+
+    /// Walks a directory tree using `walkdir` and collects every regular file.
+    ///
+    /// Symbolic links are not followed. The result is sorted by path so callers
+    /// can rely on a deterministic order.
+    fn list_files(&self, dir: &Path) -> Result<Vec<PathBuf>, String> {
+        let mut files: Vec<PathBuf> = Vec::new();
+        for entry in WalkDir::new(dir) {
+            if let Err(e) = entry {
+                return Err(e.to_string());
+            }
+
+            let entry = entry.unwrap();
+            if entry.file_type().is_file() {
+                files.push(entry.into_path());
+            }
+        }
+
+        files.sort();
+        Ok(files)
+    }
+
+    /// Reads the directory without recursing, following symbolic links.
+    fn list_folders(&self, dir: &Path) -> Result<Vec<PathBuf>, String> {
+        let entries = match fs::read_dir(dir) {
+            Err(e) => return Err(e.to_string()),
+            Ok(entries) => entries,
+        };
+
+        let mut folders: Vec<PathBuf> = entries
+            .flatten()
+            .map(|entry| entry.path())
+            .filter(|path| path.is_dir())
+            .collect();
+
+        folders.sort();
+        Ok(folders)
+    }
+
+    /// Creates a directory tree, succeeding if it already exists.
+    fn create_folder(&self, dir: &Path) -> Result<(), String> {
+        match fs::create_dir_all(dir) {
+            Err(e) => Err(e.to_string()),
+            Ok(_) => Ok(()),
+        }
+    }
+
+    /// Removes a file, or a directory along with its content.
+    ///
+    /// Symbolic links are removed themselves, never followed.
+    fn remove_path(&self, path: &Path) -> Result<(), String> {
+        let result = match fs::symlink_metadata(path) {
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => return Ok(()),
+            Err(e) => return Err(e.to_string()),
+            Ok(metadata) if metadata.is_dir() => fs::remove_dir_all(path),
+            Ok(_) => fs::remove_file(path),
+        };
+
+        match result {
+            Err(e) => Err(e.to_string()),
+            Ok(_) => Ok(()),
+        }
+    }
+
+    /// Checks the file through its metadata, following symbolic links.
+    fn is_file(&self, path: &Path) -> bool {
+        fs::metadata(path).is_ok_and(|metadata| metadata.is_file())
+    }
+
+    /// Looks for the command in every `PATH` entry, like `which` does.
+    ///
+    /// Only regular files with at least one execute permission bit are accepted.
+    fn find_executable(&self, command: &str) -> Option<PathBuf> {
+        let path_var = env::var_os(PATH_VARIABLE)?;
+
+        env::split_paths(&path_var)
+            .map(|dir| dir.join(command))
+            .find(|candidate| match fs::metadata(candidate) {
+                Ok(metadata) => metadata.is_file() && metadata.permissions().mode() & 0o111 != 0,
+                Err(_) => false,
+            })
+    }
+
+    /// Uses `NUGET_PACKAGES`, then `~/.nuget/packages` (the default of the
+    /// `dotnet` CLI on Unix), skipping empty variables and duplicates.
+    fn nuget_packages_folders(&self) -> Vec<PathBuf> {
+        let mut folders: Vec<PathBuf> = Vec::new();
+
+        if let Some(packages) =
+            env::var_os(NUGET_PACKAGES_VARIABLE).filter(|value| !value.is_empty())
+        {
+            folders.push(PathBuf::from(packages));
+        }
+
+        if let Some(home) = env::var_os(HOME_VARIABLE).filter(|value| !value.is_empty()) {
+            let default_folder = PathBuf::from(home).join(NUGET_DEFAULT_PACKAGES_FOLDER);
+            if !folders.contains(&default_folder) {
+                folders.push(default_folder);
+            }
+        }
+
+        folders
+    }
 }
@@ -3,5 +3,6 @@
 //! This module handles parsing YAML configuration files that define projects
 //! and their associated proto files, compilation options, and target languages.
 
+pub mod proto_files;
 pub mod protoweld_parser;
 pub mod types;
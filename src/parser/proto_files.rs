@@ -0,0 +1,241 @@
+//! Expansion of the glob and directory entries of `associated_proto_files`.
+//!
+//! Entries are matched component by component against the files found with
+//! `walkdir`, supporting `*` and `?` inside a component and `**` for any
+//! number of folders.
+
+use std::path::{Component, Path, PathBuf};
+
+use walkdir::WalkDir;
+
+/// Extension of the files picked up from directories and glob patterns.
+static PROTO_EXTENSION: &str = "proto";
+
+/// Wildcard matching any number of folders.
+static RECURSIVE_WILDCARD: &str = "**";
+
+/// Characters turning an entry into a glob pattern.
+static GLOB_CHARACTERS: [char; 2] = ['*', '?'];
+
+/// Expands the entries of `associated_proto_files` into the proto files to compile.
+///
+/// # Arguments
+///
+/// * `project` - Project name (used for error messages)
+/// * `entries` - Absolute paths, directories or glob patterns
+/// * `excludes` - Absolute paths, directories or glob patterns to leave out
+///
+/// # Returns
+///
+/// * `Ok(Vec<String>)` - Files in the order of the entries, each entry's matches
+///   sorted and duplicates removed
+/// * `Err(String)` - A directory or pattern matched no proto file
+///
+/// # Behavior
+///
+/// - Plain file paths are kept as written, even if they don't exist
+/// - Directories are expanded to every `.proto` file below them
+/// - Patterns are expanded to every `.proto` file they match
+/// - A file is excluded when an exclude entry matches it or one of its folders
+pub(crate) fn expand_proto_files(
+    project: &str,
+    entries: &[String],
+    excludes: &[String],
+) -> Result<Vec<String>, String> {
+    let exclude_patterns: Vec<Vec<String>> =
+        excludes.iter().map(|exclude| components(exclude)).collect();
+    let is_excluded = |file: &Path| -> bool {
+        let file_parts = components(&file.to_string_lossy());
+        (1..=file_parts.len()).any(|len| {
+            exclude_patterns
+                .iter()
+                .any(|pattern| matches_path(pattern, &file_parts[..len]))
+        })
+    };
+
+    let mut proto_files: Vec<String> = Vec::new();
+
+    for entry in entries.iter() {
+        let entry_path = PathBuf::from(entry);
+        let is_pattern = entry.contains(GLOB_CHARACTERS);
+
+        if !is_pattern && !entry_path.is_dir() {
+            if !is_excluded(&entry_path) && !proto_files.contains(entry) {
+                proto_files.push(entry.clone());
+            }
+            continue;
+        }
+
+        let pattern: Vec<String> = components(entry);
+        let walk_root: PathBuf = match is_pattern {
+            true => pattern
+                .iter()
+                .take_while(|part| !part.contains(GLOB_CHARACTERS))
+                .fold(PathBuf::from("/"), |root, part| root.join(part)),
+            false => entry_path.clone(),
+        };
+
+        let mut matches: Vec<String> = WalkDir::new(&walk_root)
+            .follow_links(true)
+            .into_iter()
+            .filter_map(|dir_entry| dir_entry.ok())
+            .filter(|dir_entry| dir_entry.file_type().is_file())
+            .map(|dir_entry| dir_entry.into_path())
+            .filter(|file| file.extension().is_some_and(|ext| ext == PROTO_EXTENSION))
+            .filter(|file| {
+                !is_pattern || matches_path(&pattern, &components(&file.to_string_lossy()))
+            })
+            .map(|file| file.to_string_lossy().to_string())
+            .collect();
+
+        if matches.is_empty() {
+            return Err(format!(
+                "The entry {} of project {} matched no proto files",
+                entry, project
+            ));
+        }
+
+        matches.sort();
+        for file in matches.into_iter() {
+            if !is_excluded(Path::new(&file)) && !proto_files.contains(&file) {
+                proto_files.push(file);
+            }
+        }
+    }
+
+    Ok(proto_files)
+}
+
+/// Splits a path into its normal components.
+fn components(path: &str) -> Vec<String> {
+    Path::new(path)
+        .components()
+        .filter_map(|component| match component {
+            Component::Normal(name) => Some(name.to_string_lossy().to_string()),
+            _ => None,
+        })
+        .collect()
+}
+
+/// Checks whether the components of a path match the components of a pattern.
+fn matches_path(pattern: &[String], path: &[String]) -> bool {
+    match pattern.split_first() {
+        None => path.is_empty(),
+        Some((part, rest)) if part == RECURSIVE_WILDCARD => {
+            (0..=path.len()).any(|skip| matches_path(rest, &path[skip..]))
+        }
+        Some((part, rest)) => match path.split_first() {
+            Some((name, path_rest)) => matches_name(part, name) && matches_path(rest, path_rest),
+            None => false,
+        },
+    }
+}
+
+/// Checks whether a single path component matches a pattern component,
+/// where `*` matches any characters and `?` matches exactly one.
+fn matches_name(pattern: &str, name: &str) -> bool {
+    let pattern: Vec<char> = pattern.chars().collect();
+    let name: Vec<char> = name.chars().collect();
+
+    // Positions to resume from when a `*` has to absorb one more character
+    let (mut p, mut n) = (0, 0);
+    let mut backtrack: Option<(usize, usize)> = None;
+
+    while n < name.len() {
+        match pattern.get(p) {
+            Some('*') => {
+                backtrack = Some((p, n));
+                p += 1;
+            }
+            Some(c) if *c == '?' || *c == name[n] => {
+                p += 1;
+                n += 1;
+            }
+            _ => match backtrack {
+                Some((star, star_n)) => {
+                    p = star + 1;
+                    n = star_n + 1;
+                    backtrack = Some((star, star_n + 1));
+                }
+                None => return false,
+            },
+        }
+    }
+
+    pattern[p..].iter().all(|c| *c == '*')
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::fs;
+
+    fn path(parts: &[&str]) -> Vec<String> {
+        parts.iter().map(|part| part.to_string()).collect()
+    }
+
+    #[test]
+    fn matches_wildcards_within_a_name() {
+        assert!(matches_name("*.proto", "users.proto"));
+        assert!(matches_name("user?.proto", "users.proto"));
+        assert!(matches_name("*_v*.proto", "users_v1.proto"));
+        assert!(matches_name("*", ""));
+        assert!(!matches_name("user?.proto", "user.proto"));
+        assert!(!matches_name("*.proto", "users.protobuf"));
+    }
+
+    #[test]
+    fn matches_any_number_of_folders_with_double_star() {
+        let pattern = path(&["protos", "**", "*.proto"]);
+
+        assert!(matches_path(&pattern, &path(&["protos", "users.proto"])));
+        assert!(matches_path(
+            &pattern,
+            &path(&["protos", "a", "b", "users.proto"])
+        ));
+        assert!(!matches_path(&pattern, &path(&["other", "users.proto"])));
+        assert!(!matches_path(
+            &path(&["protos", "*.proto"]),
+            &path(&["protos", "a", "users.proto"])
+        ));
+    }
+
+    #[test]
+    fn expands_directories_and_patterns_with_excludes() {
+        let root =
+            std::env::temp_dir().join(format!("protoweld-proto-files-{}", std::process::id()));
+        for file in [
+            "users/v1/users.proto",
+            "users/v1/internal/debug.proto",
+            "roles/roles.proto",
+            "roles/notes.txt",
+        ] {
+            let file = root.join(file);
+            fs::create_dir_all(file.parent().unwrap()).unwrap();
+            fs::write(&file, "").unwrap();
+        }
+        let absolute = |relative: &str| root.join(relative).to_string_lossy().to_string();
+
+        let files = expand_proto_files(
+            "test",
+            &[
+                absolute("users/**/*.proto"),
+                absolute("roles"),
+                absolute("missing.proto"),
+            ],
+            &[absolute("users/v1/internal")],
+        );
+        let no_match = expand_proto_files("test", &[absolute("roles/*.json")], &[]);
+        fs::remove_dir_all(&root).unwrap();
+
+        assert_eq!(
+            files.unwrap(),
+            vec![
+                absolute("users/v1/users.proto"),
+                absolute("roles/roles.proto"),
+                absolute("missing.proto"),
+            ]
+        );
+        assert!(no_match.is_err());
+    }
+}
@@ -1,15 +1,30 @@
 //! Implementation of the Protoweld parser for YAML configuration files.
 
-use std::fs;
+use std::{
+    fs,
+    path::{Component, Path, PathBuf},
+};
 
-use crate::parser::types::{IProtoweldParser, ProtoweldParser};
+use crate::{
+    os::{shared::get_os_manager, types::OSManager},
+    parser::{
+        proto_files::expand_proto_files,
+        types::{IProtoweldParser, Project, ProtoweldParser},
+    },
+    proto::imports::resolve_transitive_imports,
+};
 
 impl IProtoweldParser for ProtoweldParser {
     /// Parses a YAML configuration file into a `ProtoweldParser` structure.
     ///
     /// This implementation reads the file from disk, deserializes it from YAML format,
     /// and validates the structure. The file should contain an `active_projects` array
-    /// with project configurations.
+    /// with project configurations. Top-level defaults (such as `include_paths`)
+    /// are applied to every project that doesn't override them.
+    ///
+    /// Relative paths of every project are resolved against the folder of the
+    /// configuration file (or the top-level `base_dir`), so the configuration
+    /// behaves the same whatever directory protoweld is launched from.
     ///
     /// # Arguments
     ///
@@ -26,6 +41,9 @@ impl IProtoweldParser for ProtoweldParser {
     /// - The file cannot be read (file not found, permission denied, etc.)
     /// - The YAML content is malformed or doesn't match the expected structure
     /// - Required fields are missing from the configuration
+    /// - The configured `base_dir` doesn't exist
+    /// - A directory or glob pattern in `associated_proto_files` matches no proto file
+    /// - With `resolve_imports`, an imported file can't be found in the include paths
     fn parse(filename: &str) -> Result<ProtoweldParser, String> {
         let content = fs::read_to_string(filename);
         if let Err(error) = content {
@@ -37,6 +55,188 @@ impl IProtoweldParser for ProtoweldParser {
             return Err(error.to_string());
         }
 
-        Ok(yaml_result.unwrap())
+        let mut parser = yaml_result.unwrap();
+
+        if parser.jobs == Some(0) {
+            return Err(String::from("jobs must be at least 1"));
+        }
+
+        let base_dir: PathBuf = match &parser.base_dir {
+            Some(base_dir) => match fs::canonicalize(base_dir) {
+                Ok(base_dir) => base_dir,
+                Err(error) => return Err(format!("Invalid base_dir {}: {}", base_dir, error)),
+            },
+            None => match fs::canonicalize(filename) {
+                Ok(config_file) => config_file
+                    .parent()
+                    .map(Path::to_path_buf)
+                    .unwrap_or_default(),
+                Err(error) => return Err(error.to_string()),
+            },
+        };
+
+        let os_manager: Box<dyn OSManager> = get_os_manager().map_err(String::from)?;
+
+        for project in parser.active_projects.iter_mut() {
+            if project.include_paths.is_none() {
+                project.include_paths = Some(parser.include_paths.clone());
+            }
+
+            resolve_project_paths(project, &base_dir);
+
+            project.associated_proto_files = expand_proto_files(
+                &project.path,
+                &project.associated_proto_files,
+                &project.exclude_proto_files,
+            )?;
+
+            if project.resolve_imports {
+                project.associated_proto_files = resolve_transitive_imports(
+                    os_manager.as_ref(),
+                    &project.associated_proto_files,
+                    &project.include_roots(),
+                )?;
+            }
+        }
+
+        Ok(parser)
+    }
+}
+
+/// Resolves every relative path of a project against `base_dir`.
+///
+/// `include_paths` and `compile_options` are left untouched, since `protoc`
+/// runs from `base_dir` and resolves them itself. Plugins given as a bare name
+/// are looked up in PATH, so only the ones containing a folder are resolved.
+fn resolve_project_paths(project: &mut Project, base_dir: &Path) {
+    project.base_dir = base_dir.to_path_buf();
+
+    project.associated_proto_files = project
+        .associated_proto_files
+        .iter()
+        .map(|proto| resolve_path(base_dir, proto))
+        .collect();
+
+    project.exclude_proto_files = project
+        .exclude_proto_files
+        .iter()
+        .map(|exclude| resolve_path(base_dir, exclude))
+        .collect();
+
+    project.compiled_proto_folder = resolve_path(base_dir, &project.compiled_proto_folder);
+
+    for path in [&mut project.messages_out, &mut project.grpc_out]
+        .into_iter()
+        .flatten()
+    {
+        *path = resolve_path(base_dir, path);
+    }
+
+    if let Some(plugin_path) = &project.plugin_path {
+        project.plugin_path = Some(resolve_plugin_path(base_dir, plugin_path));
+    }
+
+    for generator in project.generators.iter_mut() {
+        if let Some(out_folder) = &generator.out_folder {
+            generator.out_folder = Some(resolve_path(base_dir, out_folder));
+        }
+
+        if let Some(plugin_path) = &generator.plugin_path {
+            generator.plugin_path = Some(resolve_plugin_path(base_dir, plugin_path));
+        }
+    }
+}
+
+/// Resolves a plugin path like [`resolve_path`], unless it is an executable name.
+fn resolve_plugin_path(base_dir: &Path, plugin_path: &String) -> String {
+    match is_executable_name(plugin_path) {
+        true => plugin_path.clone(),
+        false => resolve_path(base_dir, plugin_path),
+    }
+}
+
+/// Whether a plugin path is a bare executable name (e.g., `protoc-gen-foo`)
+/// meant to be looked up in PATH, rather than a path to the executable.
+pub fn is_executable_name(plugin_path: &str) -> bool {
+    !plugin_path.contains('/')
+}
+
+/// Joins a relative path to `base_dir`, dropping `.` components.
+/// Absolute paths are returned unchanged.
+fn resolve_path(base_dir: &Path, path: &String) -> String {
+    let path = PathBuf::from(path);
+    if path.is_absolute() {
+        return path.to_string_lossy().to_string();
+    }
+
+    let mut resolved: PathBuf = base_dir.to_path_buf();
+    resolved.extend(
+        path.components()
+            .filter(|component| !matches!(component, Component::CurDir)),
+    );
+
+    resolved.to_string_lossy().to_string()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn resolves_relative_paths_against_base_dir() {
+        let base_dir: &Path = Path::new("/work/protos");
+
+        assert_eq!(
+            resolve_path(base_dir, &String::from("./out/go")),
+            "/work/protos/out/go"
+        );
+        assert_eq!(
+            resolve_path(base_dir, &String::from("../shared")),
+            "/work/protos/../shared"
+        );
+        assert_eq!(
+            resolve_path(base_dir, &String::from("/opt/out")),
+            "/opt/out"
+        );
+    }
+
+    #[test]
+    fn resolves_project_paths_but_not_executable_names() {
+        let mut project: Project = serde_yaml::from_str(
+            "path: api\nlang: Cpp\ncompiled_proto_folder: ./out\nmessages_out: out/messages\nassociated_proto_files: [protos/users.proto, /abs/roles.proto]\ninclude_paths: [protos]\nplugin_path: grpc_cpp_plugin\ngenerators:\n  - name: doc\n    plugin_path: ./bin/protoc-gen-doc\n    out_folder: docs\n  - name: validate\n    plugin_path: protoc-gen-validate\n",
+        )
+        .unwrap();
+
+        resolve_project_paths(&mut project, Path::new("/work"));
+
+        assert_eq!(project.base_dir, PathBuf::from("/work"));
+        assert_eq!(project.compiled_proto_folder, "/work/out");
+        assert_eq!(project.messages_out.as_deref(), Some("/work/out/messages"));
+        assert_eq!(project.grpc_out, None);
+        assert_eq!(
+            project.associated_proto_files,
+            vec!["/work/protos/users.proto", "/abs/roles.proto"]
+        );
+        assert_eq!(project.include_paths, Some(vec![String::from("protos")]));
+        assert_eq!(project.plugin_path.as_deref(), Some("grpc_cpp_plugin"));
+        assert_eq!(
+            project.generators[0].plugin_path.as_deref(),
+            Some("/work/bin/protoc-gen-doc")
+        );
+        assert_eq!(
+            project.generators[0].out_folder.as_deref(),
+            Some("/work/docs")
+        );
+        assert_eq!(
+            project.generators[1].plugin_path.as_deref(),
+            Some("protoc-gen-validate")
+        );
+
+        project.plugin_path = Some(String::from("tools/grpc_cpp_plugin"));
+        resolve_project_paths(&mut project, Path::new("/work"));
+        assert_eq!(
+            project.plugin_path.as_deref(),
+            Some("/work/tools/grpc_cpp_plugin")
+        );
     }
 }
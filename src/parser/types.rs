@@ -1,7 +1,19 @@
 //! Type definitions for Protoweld configuration structures.
 
-use serde::Deserialize;
-use std::{collections::HashMap, str::FromStr};
+use serde::{
+    de::{self, MapAccess, SeqAccess, Visitor},
+    Deserialize, Deserializer,
+};
+use std::{fmt, path::PathBuf, str::FromStr};
+
+/// Protoc flag adding a folder to the import search path.
+pub(crate) static PROTO_PATH_FLAG: &str = "--proto_path";
+
+/// Short form of [`PROTO_PATH_FLAG`].
+pub(crate) static PROTO_PATH_SHORT_FLAG: &str = "-I";
+
+/// Separator of the folders given to a single `--proto_path` flag.
+static PROTO_PATH_SEPARATOR: char = ':';
 
 /// Trait for parsing Protoweld configuration files.
 ///
@@ -33,6 +45,18 @@ pub enum Lang {
     DotNet,
     /// Rust programming language
     Rust,
+    /// Python programming language
+    Python,
+    /// TypeScript programming language
+    TypeScript,
+    /// Java programming language
+    Java,
+    /// Kotlin programming language
+    Kotlin,
+    /// C++ programming language
+    Cpp,
+    /// Only the generators listed in the project's `generators` field
+    Custom,
 }
 
 impl FromStr for Lang {
@@ -42,7 +66,7 @@ impl FromStr for Lang {
     ///
     /// # Arguments
     ///
-    /// * `s` - String representation of the language (e.g., "GoLang", "DotNet", "Rust")
+    /// * `s` - String representation of the language (e.g., "GoLang", "DotNet", "Rust", "Python", "TypeScript", "Java", "Kotlin", "Cpp", "Custom")
     ///
     /// # Returns
     ///
@@ -53,6 +77,12 @@ impl FromStr for Lang {
             "GoLang" => Ok(Lang::GoLang),
             "DotNet" => Ok(Lang::DotNet),
             "Rust" => Ok(Lang::Rust),
+            "Python" => Ok(Lang::Python),
+            "TypeScript" => Ok(Lang::TypeScript),
+            "Java" => Ok(Lang::Java),
+            "Kotlin" => Ok(Lang::Kotlin),
+            "Cpp" => Ok(Lang::Cpp),
+            "Custom" => Ok(Lang::Custom),
             _ => Err("Unsupported lang"),
         }
     }
@@ -69,18 +99,472 @@ pub struct Project {
     pub path: String,
     /// Output directory where compiled proto files will be placed
     pub compiled_proto_folder: String,
+    /// Output directory for message types (defaults to `compiled_proto_folder`)
+    pub messages_out: Option<String>,
+    /// Output directory for gRPC service code (defaults to `compiled_proto_folder`)
+    pub grpc_out: Option<String>,
+    /// Generate message types only, skipping gRPC service code entirely
+    #[serde(default)]
+    pub messages_only: bool,
+    /// Which side of the gRPC services to generate
+    #[serde(default)]
+    pub grpc_services: GrpcServices,
     /// List of paths to `.proto` files that should be compiled for this project
+    ///
+    /// Entries may also be directories (every `.proto` file below them) or glob
+    /// patterns such as `entities/protos/security/**/*.proto`. The parser expands
+    /// them, so compilers only see file paths.
     pub associated_proto_files: Vec<String>,
+    /// Paths, directories or glob patterns removed from `associated_proto_files`
+    #[serde(default)]
+    pub exclude_proto_files: Vec<String>,
+    /// Also compile every proto file imported, directly or not, by `associated_proto_files`
+    #[serde(default)]
+    pub resolve_imports: bool,
     /// Optional path to a custom gRPC plugin (required for .NET projects)
+    ///
+    /// TypeScript projects also accept a directory containing the
+    /// `protoc-gen-*` executables (e.g., `./web/node_modules/.bin`).
     pub plugin_path: Option<String>,
     /// Target programming language for code generation
     pub lang: Lang,
+    /// Folders where `protoc` looks for imported proto files (`--proto_path`)
+    ///
+    /// When absent, the top-level `include_paths` of the configuration are used.
+    pub include_paths: Option<Vec<String>>,
     /// Additional compilation options passed to `protoc`
     ///
     /// These are key-value pairs where keys are protoc flags (e.g., "-I", "--include_imports")
     /// and values are the flag values. Empty strings indicate flags without values.
+    /// They are passed to protoc in the order they are written.
+    #[serde(default)]
+    pub compile_options: CompileOptions,
+    /// Arbitrary protoc generators, run in the same protoc invocation as the
+    /// language generators (required for `Custom` projects)
+    #[serde(default)]
+    pub generators: Vec<GeneratorConfig>,
+    /// Python-specific settings (only used when `lang` is `Python`)
+    #[serde(default)]
+    pub python_options: PythonOptions,
+    /// TypeScript-specific settings (only used when `lang` is `TypeScript`)
+    #[serde(default)]
+    pub typescript_options: TypeScriptOptions,
+    /// JVM-specific settings (only used when `lang` is `Java` or `Kotlin`)
+    #[serde(default)]
+    pub jvm_options: JvmOptions,
+    /// C++-specific settings (only used when `lang` is `Cpp`)
+    #[serde(default)]
+    pub cpp_options: CppOptions,
+    /// Rust-specific settings (only used when `lang` is `Rust`)
+    #[serde(default)]
+    pub rust_options: RustOptions,
+    /// Go-specific settings (only used when `lang` is `GoLang`)
+    #[serde(default)]
+    pub go_options: GoOptions,
+    /// .NET-specific settings (only used when `lang` is `DotNet`)
+    #[serde(default)]
+    pub dotnet_options: DotNetOptions,
+    /// Folder the project's relative paths were resolved against, and where
+    /// `protoc` runs (set by the parser)
+    #[serde(skip)]
+    pub base_dir: PathBuf,
+}
+
+impl Project {
+    /// Returns the folder where message types are generated.
+    pub fn messages_folder(&self) -> &String {
+        self.messages_out
+            .as_ref()
+            .unwrap_or(&self.compiled_proto_folder)
+    }
+
+    /// Returns the folder where gRPC service code is generated.
+    pub fn grpc_folder(&self) -> &String {
+        self.grpc_out.as_ref().unwrap_or(&self.compiled_proto_folder)
+    }
+
+    /// Returns every folder the project writes generated files to: the
+    /// `compiled_proto_folder`, the message and gRPC folders, and the output
+    /// folders of the additional generators.
+    pub fn output_folders(&self) -> Vec<PathBuf> {
+        let mut folders: Vec<PathBuf> = vec![
+            PathBuf::from(&self.compiled_proto_folder),
+            PathBuf::from(self.messages_folder()),
+            PathBuf::from(self.grpc_folder()),
+        ];
+        folders.extend(
+            self.generators
+                .iter()
+                .filter_map(|generator| generator.out_folder.as_ref().map(PathBuf::from)),
+        );
+
+        folders
+    }
+
+    /// Returns the folders `protoc` searches for imports, in order.
+    ///
+    /// These are the `include_paths` followed by the `-I`/`--proto_path`
+    /// entries of `compile_options`, resolved against `base_dir`. Without any
+    /// of them, `protoc` searches `base_dir`, where it runs.
+    pub fn include_roots(&self) -> Vec<PathBuf> {
+        let mut roots: Vec<PathBuf> = self
+            .include_paths
+            .iter()
+            .flatten()
+            .map(|include_path| self.base_dir.join(include_path))
+            .collect();
+
+        for (key, value) in self.compile_options.iter() {
+            if key == PROTO_PATH_FLAG || key == PROTO_PATH_SHORT_FLAG {
+                roots.extend(
+                    value
+                        .split(PROTO_PATH_SEPARATOR)
+                        .map(|include_path| self.base_dir.join(include_path)),
+                );
+            }
+        }
+
+        if roots.is_empty() {
+            roots.push(self.base_dir.clone());
+        }
+
+        roots
+    }
+
+    /// Returns whether gRPC service code must be generated.
+    pub fn generates_grpc(&self) -> bool {
+        !self.messages_only && self.grpc_services != GrpcServices::None
+    }
+}
+
+/// Ordered protoc flags of a project.
+///
+/// Accepts either a map (`"-I": entities`) or, to repeat a flag, a list of
+/// `[flag, value]` pairs (`- ["-I", "entities"]`) where the value may be
+/// omitted (`- ["--include_imports"]` or `- "--include_imports"`). Both forms
+/// keep the order of the configuration file, so protoc command lines are
+/// reproducible between runs.
+#[derive(Debug, Default, Clone)]
+pub struct CompileOptions(pub Vec<(String, String)>);
+
+impl CompileOptions {
+    /// Returns the `(flag, value)` pairs in configuration order.
+    pub fn iter(&self) -> std::slice::Iter<'_, (String, String)> {
+        self.0.iter()
+    }
+}
+
+/// An entry of the list form of `compile_options`.
+#[derive(Deserialize)]
+#[serde(untagged)]
+enum CompileOptionEntry {
+    /// A flag without value, e.g. `"--include_imports"`
+    Flag(String),
+    /// A `[flag]` or `[flag, value]` pair
+    Pair(Vec<String>),
+}
+
+impl<'de> Deserialize<'de> for CompileOptions {
+    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        deserializer.deserialize_any(CompileOptionsVisitor)
+    }
+}
+
+/// Visitor reading both forms of `compile_options` while keeping their order.
+struct CompileOptionsVisitor;
+
+impl<'de> Visitor<'de> for CompileOptionsVisitor {
+    type Value = CompileOptions;
+
+    fn expecting(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+        formatter.write_str("a map of protoc flags or a list of [flag, value] pairs")
+    }
+
+    fn visit_unit<E: de::Error>(self) -> Result<Self::Value, E> {
+        Ok(CompileOptions::default())
+    }
+
+    fn visit_map<A: MapAccess<'de>>(self, mut map: A) -> Result<Self::Value, A::Error> {
+        let mut options: Vec<(String, String)> = Vec::new();
+        while let Some((flag, value)) = map.next_entry::<String, Option<String>>()? {
+            options.push((flag, value.unwrap_or_default()));
+        }
+
+        Ok(CompileOptions(options))
+    }
+
+    fn visit_seq<A: SeqAccess<'de>>(self, mut seq: A) -> Result<Self::Value, A::Error> {
+        let mut options: Vec<(String, String)> = Vec::new();
+        while let Some(entry) = seq.next_element::<CompileOptionEntry>()? {
+            let option = match entry {
+                CompileOptionEntry::Flag(flag) => (flag, String::new()),
+                CompileOptionEntry::Pair(pair) => match <[String; 2]>::try_from(pair) {
+                    Ok([flag, value]) => (flag, value),
+                    Err(pair) => match <[String; 1]>::try_from(pair) {
+                        Ok([flag]) => (flag, String::new()),
+                        Err(pair) => {
+                            return Err(de::Error::custom(format!(
+                                "compile option {:?} must be a [flag] or [flag, value] list",
+                                pair
+                            )))
+                        }
+                    },
+                },
+            };
+            options.push(option);
+        }
+
+        Ok(CompileOptions(options))
+    }
+}
+
+/// Side of the gRPC services generated for a project.
+///
+/// Each compiler translates it into the native options of its gRPC plugin.
+#[derive(Debug, Default, PartialEq, Deserialize, Copy, Clone)]
+pub enum GrpcServices {
+    /// Client stubs only
+    #[serde(alias = "client")]
+    Client,
+    /// Server bases only
+    #[serde(alias = "server")]
+    Server,
+    /// Both client stubs and server bases
+    #[default]
+    #[serde(alias = "both")]
+    Both,
+    /// No gRPC code at all, same as `messages_only`
+    #[serde(alias = "none")]
+    None,
+}
+
+/// A protoc generator declared directly in the configuration.
+///
+/// Any `protoc-gen-<name>` plugin (or protoc built-in generator) can be run
+/// this way, e.g. `protoc-gen-doc`, `protoc-gen-validate` or in-house plugins.
+#[derive(Debug, Deserialize)]
+pub struct GeneratorConfig {
+    /// Generator name: protoc receives `--<name>_out` and runs `protoc-gen-<name>`
+    pub name: String,
+    /// Parameters passed to the generator through `--<name>_opt`
     #[serde(default)]
-    pub compile_options: HashMap<String, String>
+    pub options: Vec<String>,
+    /// Path (or executable name in PATH) of the plugin, when it isn't `protoc-gen-<name>`
+    pub plugin_path: Option<String>,
+    /// Output folder for this generator (defaults to `compiled_proto_folder`)
+    pub out_folder: Option<String>,
+}
+
+/// Python-specific compilation settings.
+#[derive(Debug, Default, Deserialize)]
+pub struct PythonOptions {
+    /// Also generate `.pyi` typed stubs using `--pyi_out`
+    #[serde(default)]
+    pub typed_stubs: bool,
+}
+
+/// Code generator used for TypeScript projects.
+#[derive(Debug, Default, PartialEq, Deserialize, Copy, Clone)]
+pub enum TypeScriptGenerator {
+    /// `ts-proto` (`protoc-gen-ts_proto`), messages and services in one file
+    #[default]
+    TsProto,
+    /// `protobuf-es` (`protoc-gen-es`)
+    ProtobufEs,
+    /// `protobuf-es` messages plus `connect-es` services (`protoc-gen-connect-es`)
+    ConnectEs,
+}
+
+/// TypeScript-specific compilation settings.
+#[derive(Debug, Default, Deserialize)]
+pub struct TypeScriptOptions {
+    /// Generator flavour used to produce the TypeScript code
+    #[serde(default)]
+    pub generator: TypeScriptGenerator,
+}
+
+/// Folder layout used for the generated JVM sources.
+#[derive(Debug, Default, PartialEq, Deserialize, Copy, Clone)]
+pub enum JvmLayout {
+    /// Every generator writes directly into `compiled_proto_folder`
+    #[default]
+    Flat,
+    /// `protobuf-maven-plugin` layout: `java/`, `grpc-java/` and `kotlin/`
+    Maven,
+    /// `protobuf-gradle-plugin` layout: `java/`, `grpc/` and `kotlin/`
+    Gradle,
+}
+
+/// Java and Kotlin compilation settings.
+#[derive(Debug, Default, Deserialize)]
+pub struct JvmOptions {
+    /// Folder layout of the generated sources inside `compiled_proto_folder`
+    #[serde(default)]
+    pub layout: JvmLayout,
+}
+
+/// C++-specific compilation settings.
+#[derive(Debug, Default, Deserialize)]
+pub struct CppOptions {
+    /// Write a `protoweld_generated.cmake` file listing the generated sources
+    #[serde(default)]
+    pub cmake_fragment: bool,
+}
+
+/// Root module file declaring every package generated for a Rust project.
+#[derive(Debug, Default, PartialEq, Deserialize, Copy, Clone)]
+pub enum RustRootModule {
+    /// `mod.rs`, to include the output folder as a module of an existing crate
+    #[default]
+    ModRs,
+    /// `lib.rs`, to use the output folder as the source of a generated crate
+    LibRs,
+    /// No root module file
+    None,
+}
+
+/// How the Rust code is generated.
+#[derive(Debug, Default, PartialEq, Deserialize, Copy, Clone)]
+pub enum RustBackend {
+    /// `protoc-gen-prost` and `protoc-gen-tonic` plugins run by protoc
+    #[default]
+    Plugins,
+    /// `prost-build` and `tonic-build` run by protoweld on the descriptor set
+    /// written by protoc, without any plugin installed
+    Library,
+}
+
+/// A Rust attribute added to the generated items matching a proto path.
+#[derive(Debug, Deserialize)]
+pub struct RustAttribute {
+    /// Fully qualified proto path (e.g., `.acme.billing.v1.Invoice`), or `.` for every item
+    pub path: String,
+    /// Attribute as written in Rust (e.g., `#[derive(serde::Serialize)]`)
+    pub attribute: String,
+}
+
+/// Proto types reused from another crate instead of being generated.
+#[derive(Debug, Deserialize)]
+pub struct RustExternPath {
+    /// Fully qualified proto package or type (e.g., `.acme.common`)
+    pub proto_path: String,
+    /// Rust path replacing it (e.g., `::acme_common::protos`)
+    pub rust_path: String,
+}
+
+/// Rust-specific compilation settings.
+#[derive(Debug, Default, Deserialize)]
+pub struct RustOptions {
+    /// Code generation backend
+    #[serde(default)]
+    pub backend: RustBackend,
+    /// Root module file written in the messages folder
+    #[serde(default)]
+    pub root_module: RustRootModule,
+    /// Attributes added to messages and enums
+    #[serde(default)]
+    pub type_attributes: Vec<RustAttribute>,
+    /// Attributes added to message fields
+    #[serde(default)]
+    pub field_attributes: Vec<RustAttribute>,
+    /// Proto packages or types mapped to existing Rust types
+    #[serde(default)]
+    pub extern_paths: Vec<RustExternPath>,
+    /// Proto paths of the `bytes` fields generated as `bytes::Bytes` (`.` for all of them)
+    #[serde(default)]
+    pub bytes: Vec<String>,
+}
+
+/// How the Go generators name their output files.
+#[derive(Debug, Default, PartialEq, Deserialize, Copy, Clone)]
+pub enum GoPaths {
+    /// Files are placed by their `go_package` import path (without the
+    /// configured `module` prefix)
+    #[default]
+    #[serde(alias = "import")]
+    Import,
+    /// Files are placed next to each other like their proto files
+    #[serde(alias = "source_relative")]
+    SourceRelative,
+}
+
+/// Go-specific compilation settings.
+#[derive(Debug, Default, Deserialize)]
+pub struct GoOptions {
+    /// Go module the generated packages belong to (e.g., `github.com/acme/protos`)
+    pub module: Option<String>,
+    /// Output file naming of `protoc-gen-go` and `protoc-gen-go-grpc`
+    #[serde(default)]
+    pub paths: GoPaths,
+    /// Write (or update) a `go.mod` for `module` in `compiled_proto_folder`,
+    /// making the generated code a standalone Go module
+    #[serde(default)]
+    pub go_mod: bool,
+}
+
+/// Default target framework of the generated `.csproj`.
+static DOTNET_DEFAULT_TARGET_FRAMEWORK: &str = "net8.0";
+
+/// Default version of the `Google.Protobuf` package referenced by the generated `.csproj`.
+static DOTNET_DEFAULT_PROTOBUF_VERSION: &str = "3.31.1";
+
+/// Default version of the gRPC packages referenced by the generated `.csproj`.
+static DOTNET_DEFAULT_GRPC_VERSION: &str = "2.71.0";
+
+/// .NET-specific compilation settings.
+#[derive(Debug, Deserialize)]
+pub struct DotNetOptions {
+    /// Namespace stripped from `csharp_namespace` to build the folder of each
+    /// file (`--csharp_opt=base_namespace=`). An empty value lays out files by
+    /// their full namespace
+    pub base_namespace: Option<String>,
+    /// Write (or update) a class library `.csproj` in `compiled_proto_folder`
+    #[serde(default)]
+    pub csproj: bool,
+    /// Target framework of the generated `.csproj`
+    #[serde(default = "default_target_framework")]
+    pub target_framework: String,
+    /// Version of the `Google.Protobuf` package reference
+    #[serde(default = "default_protobuf_version")]
+    pub protobuf_version: String,
+    /// Version of the `Grpc.Core.Api` and `Grpc.Net.Client` package references
+    #[serde(default = "default_grpc_version")]
+    pub grpc_version: String,
+    /// Version of the `Grpc.Tools` package searched in the NuGet cache for
+    /// `grpc_csharp_plugin` when `plugin_path` is not set (default: the highest
+    /// installed one)
+    pub grpc_tools_version: Option<String>,
+}
+
+impl Default for DotNetOptions {
+    fn default() -> Self {
+        DotNetOptions {
+            base_namespace: None,
+            csproj: false,
+            target_framework: default_target_framework(),
+            protobuf_version: default_protobuf_version(),
+            grpc_version: default_grpc_version(),
+            grpc_tools_version: None,
+        }
+    }
+}
+
+/// Returns [`DOTNET_DEFAULT_TARGET_FRAMEWORK`], used as serde default.
+fn default_target_framework() -> String {
+    String::from(DOTNET_DEFAULT_TARGET_FRAMEWORK)
+}
+
+/// Returns [`DOTNET_DEFAULT_PROTOBUF_VERSION`], used as serde default.
+fn default_protobuf_version() -> String {
+    String::from(DOTNET_DEFAULT_PROTOBUF_VERSION)
+}
+
+/// Returns [`DOTNET_DEFAULT_GRPC_VERSION`], used as serde default.
+fn default_grpc_version() -> String {
+    String::from(DOTNET_DEFAULT_GRPC_VERSION)
 }
 
 /// Root structure representing the entire Protoweld configuration.
@@ -89,6 +573,76 @@ pub struct Project {
 /// all projects that need proto file compilation.
 #[derive(Debug, Deserialize)]
 pub struct ProtoweldParser {
+    /// Folder relative paths are resolved against (defaults to the folder of
+    /// the configuration file). A relative value is resolved against the
+    /// directory protoweld is launched from, so `.` restores that behavior.
+    pub base_dir: Option<String>,
+    /// Default include paths of the projects that don't set `include_paths`
+    #[serde(default)]
+    pub include_paths: Vec<String>,
+    /// Maximum number of projects compiled at the same time (default: 1).
+    /// Overridden by the `--jobs` command-line option
+    pub jobs: Option<usize>,
     /// List of all projects configured for proto file compilation
     pub active_projects: Vec<Project>,
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn compile_options(yaml: &str) -> Vec<(String, String)> {
+        serde_yaml::from_str::<CompileOptions>(yaml).unwrap().0
+    }
+
+    fn pairs(options: &[(&str, &str)]) -> Vec<(String, String)> {
+        options
+            .iter()
+            .map(|(flag, value)| (flag.to_string(), value.to_string()))
+            .collect()
+    }
+
+    #[test]
+    fn keeps_the_order_of_compile_options_maps() {
+        assert_eq!(
+            compile_options("--proto_path: protos\n-I: entities\n--include_imports:\n"),
+            pairs(&[
+                ("--proto_path", "protos"),
+                ("-I", "entities"),
+                ("--include_imports", ""),
+            ])
+        );
+        assert_eq!(
+            compile_options("-I: b\n--proto_path: a\n"),
+            pairs(&[("-I", "b"), ("--proto_path", "a")])
+        );
+    }
+
+    #[test]
+    fn keeps_repeated_flags_of_compile_options_lists() {
+        assert_eq!(
+            compile_options(
+                "- [\"-I\", entities]\n- [\"-I\", protos]\n- [--include_imports]\n- --include_source_info\n- [\"-I\", entities]\n"
+            ),
+            pairs(&[
+                ("-I", "entities"),
+                ("-I", "protos"),
+                ("--include_imports", ""),
+                ("--include_source_info", ""),
+                ("-I", "entities"),
+            ])
+        );
+    }
+
+    #[test]
+    fn reads_empty_compile_options() {
+        assert!(compile_options("null").is_empty());
+        assert!(compile_options("{}").is_empty());
+        assert!(compile_options("[]").is_empty());
+    }
+
+    #[test]
+    fn rejects_compile_options_with_too_many_values() {
+        assert!(serde_yaml::from_str::<CompileOptions>("- [\"-I\", a, b]\n").is_err());
+    }
+}
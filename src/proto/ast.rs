@@ -0,0 +1,193 @@
+//! Typed representation of a parsed proto file.
+
+/// A parsed `.proto` file.
+///
+/// Only the declarations needed by the language compilers are kept: extensions,
+/// reserved ranges and similar statements are validated but not stored.
+#[derive(Debug, Default, Clone)]
+pub struct ProtoFile {
+    /// Value of the `syntax` statement (e.g., `proto3`)
+    pub syntax: Option<String>,
+    /// Value of the `edition` statement (e.g., `2023`)
+    pub edition: Option<String>,
+    /// Declared package (e.g., `security.v1`)
+    pub package: Option<String>,
+    /// Imported files, in declaration order
+    pub imports: Vec<Import>,
+    /// File-level options (e.g., `go_package`, `java_package`)
+    pub options: Vec<ProtoOption>,
+    /// Top-level messages
+    pub messages: Vec<Message>,
+    /// Top-level enums
+    pub enums: Vec<Enum>,
+    /// Services
+    pub services: Vec<Service>,
+}
+
+impl ProtoFile {
+    /// Returns the value of a file-level option, if declared.
+    ///
+    /// Custom options are looked up with their parentheses (e.g., `(my.option)`).
+    pub fn option(&self, name: &str) -> Option<&String> {
+        find_option(&self.options, name)
+    }
+
+    /// Returns whether the file declares at least one service.
+    pub fn has_services(&self) -> bool {
+        !self.services.is_empty()
+    }
+}
+
+/// Kind of an `import` statement.
+#[derive(Debug, Default, PartialEq, Clone, Copy)]
+pub enum ImportKind {
+    /// `import "file.proto";`
+    #[default]
+    Default,
+    /// `import public "file.proto";`, re-exported to the importers of this file
+    Public,
+    /// `import weak "file.proto";`
+    Weak,
+}
+
+/// An `import` statement.
+#[derive(Debug, Default, Clone)]
+pub struct Import {
+    /// Imported path, relative to an include path
+    pub path: String,
+    /// Kind of import
+    pub kind: ImportKind,
+}
+
+/// An option declaration, at any level.
+#[derive(Debug, Default, Clone)]
+pub struct ProtoOption {
+    /// Option name, including parentheses for custom options (e.g., `(my.option).field`)
+    pub name: String,
+    /// Option value: string literals without quotes, other constants as written
+    pub value: String,
+}
+
+/// Label of a message field.
+#[derive(Debug, PartialEq, Clone, Copy)]
+pub enum FieldLabel {
+    /// `optional`
+    Optional,
+    /// `required` (proto2 only)
+    Required,
+    /// `repeated`
+    Repeated,
+}
+
+/// A message declaration.
+#[derive(Debug, Default, Clone)]
+pub struct Message {
+    /// Message name
+    pub name: String,
+    /// Fields, including the fields of its `oneof` groups
+    pub fields: Vec<Field>,
+    /// `oneof` groups
+    pub oneofs: Vec<Oneof>,
+    /// Nested messages (including proto2 groups)
+    pub messages: Vec<Message>,
+    /// Nested enums
+    pub enums: Vec<Enum>,
+    /// Message options
+    pub options: Vec<ProtoOption>,
+}
+
+impl Message {
+    /// Returns the value of a message option, if declared.
+    pub fn option(&self, name: &str) -> Option<&String> {
+        find_option(&self.options, name)
+    }
+}
+
+/// A field of a message.
+#[derive(Debug, Default, Clone)]
+pub struct Field {
+    /// Field name
+    pub name: String,
+    /// Field type as written (e.g., `string`, `.pkg.Type`, `map<string, int32>`)
+    pub type_name: String,
+    /// Field number
+    pub number: String,
+    /// Label, if any
+    pub label: Option<FieldLabel>,
+    /// Name of the `oneof` group containing the field, if any
+    pub oneof: Option<String>,
+    /// Field options (e.g., `deprecated`, `json_name`)
+    pub options: Vec<ProtoOption>,
+}
+
+impl Field {
+    /// Returns whether the field is a `map<K, V>`.
+    pub fn is_map(&self) -> bool {
+        self.type_name.starts_with("map<")
+    }
+}
+
+/// A `oneof` group.
+#[derive(Debug, Default, Clone)]
+pub struct Oneof {
+    /// Group name
+    pub name: String,
+    /// Names of the fields of the group
+    pub fields: Vec<String>,
+}
+
+/// An enum declaration.
+#[derive(Debug, Default, Clone)]
+pub struct Enum {
+    /// Enum name
+    pub name: String,
+    /// Enum values, in declaration order
+    pub values: Vec<EnumValue>,
+    /// Enum options (e.g., `allow_alias`)
+    pub options: Vec<ProtoOption>,
+}
+
+/// A value of an enum.
+#[derive(Debug, Default, Clone)]
+pub struct EnumValue {
+    /// Value name
+    pub name: String,
+    /// Value number, with its sign
+    pub number: String,
+}
+
+/// A service declaration.
+#[derive(Debug, Default, Clone)]
+pub struct Service {
+    /// Service name
+    pub name: String,
+    /// RPC methods
+    pub methods: Vec<Method>,
+    /// Service options
+    pub options: Vec<ProtoOption>,
+}
+
+/// An RPC method of a service.
+#[derive(Debug, Default, Clone)]
+pub struct Method {
+    /// Method name
+    pub name: String,
+    /// Request type as written
+    pub input_type: String,
+    /// Response type as written
+    pub output_type: String,
+    /// Whether the client streams requests
+    pub client_streaming: bool,
+    /// Whether the server streams responses
+    pub server_streaming: bool,
+    /// Method options
+    pub options: Vec<ProtoOption>,
+}
+
+/// Finds an option by name in a list of options.
+fn find_option<'a>(options: &'a [ProtoOption], name: &str) -> Option<&'a String> {
+    options
+        .iter()
+        .find(|option| option.name == name)
+        .map(|option| &option.value)
+}
@@ -0,0 +1,172 @@
+//! Discovery and resolution of `import` statements in proto files.
+
+use std::{
+    collections::HashSet,
+    path::{Component, Path, PathBuf},
+};
+
+use log::debug;
+
+use crate::{os::types::OSManager, proto::parser::parse_file};
+
+/// Prefix of the well-known types, provided by protoc and the protobuf runtimes.
+static WELL_KNOWN_TYPES_PREFIX: &str = "google/protobuf/";
+
+/// Adds the transitive imports of `proto_files` to the list.
+///
+/// Imports are resolved against `include_roots` in order, like `protoc` does.
+///
+/// # Arguments
+///
+/// * `os_manager` - OS manager used to check which include root holds an import
+/// * `proto_files` - Paths of the proto files to compile
+/// * `include_roots` - Folders where imports are looked up
+///
+/// # Returns
+///
+/// * `Ok(Vec<String>)` - `proto_files` followed by every file they import,
+///   directly or not, in discovery order
+/// * `Err(String)` - A file can't be read or parsed, or an import is not found in any root
+///
+/// # Behavior
+///
+/// Imports of the well-known types (`google/protobuf/*.proto`) that are not
+/// found in the include roots are skipped, since protoc provides them.
+pub fn resolve_transitive_imports(
+    os_manager: &dyn OSManager,
+    proto_files: &[String],
+    include_roots: &[PathBuf],
+) -> Result<Vec<String>, String> {
+    let mut closure: Vec<String> = proto_files.to_vec();
+    let mut visited: HashSet<PathBuf> = proto_files
+        .iter()
+        .map(|proto| normalize(&PathBuf::from(proto)))
+        .collect();
+
+    let mut index = 0;
+    while index < closure.len() {
+        let proto = closure[index].clone();
+        index += 1;
+
+        let imports: Vec<String> = parse_file(&PathBuf::from(&proto))?
+            .imports
+            .into_iter()
+            .map(|import| import.path)
+            .collect();
+
+        for import in imports.iter() {
+            let resolved = include_roots
+                .iter()
+                .map(|root| normalize(&root.join(import)))
+                .find(|candidate| os_manager.is_file(candidate));
+
+            match resolved {
+                Some(resolved) => {
+                    if visited.insert(resolved.clone()) {
+                        debug!("Adding {} imported by {}", resolved.display(), proto);
+                        closure.push(resolved.to_string_lossy().to_string());
+                    }
+                }
+                None if import.starts_with(WELL_KNOWN_TYPES_PREFIX) => {}
+                None => {
+                    return Err(format!(
+                        "Import {} of {} not found in any include path ({})",
+                        import,
+                        proto,
+                        include_roots
+                            .iter()
+                            .map(|root| root.to_string_lossy().to_string())
+                            .collect::<Vec<String>>()
+                            .join(", ")
+                    ))
+                }
+            }
+        }
+    }
+
+    Ok(closure)
+}
+
+/// Removes `.` and resolves `..` components lexically.
+fn normalize(path: &Path) -> PathBuf {
+    let mut normalized = PathBuf::new();
+    for component in path.components() {
+        match component {
+            Component::CurDir => {}
+            Component::ParentDir => {
+                normalized.pop();
+            }
+            _ => normalized.push(component),
+        }
+    }
+
+    normalized
+}
+
+#[cfg(test)]
+mod tests {
+    use std::fs;
+
+    use super::*;
+    use crate::os::types::UnixManager;
+
+    fn write_proto(path: &Path, imports: &[&str]) {
+        fs::create_dir_all(path.parent().unwrap()).unwrap();
+        let mut content: String = String::from("syntax = \"proto3\";\n");
+        for import in imports {
+            content += &format!("import \"{}\";\n", import);
+        }
+        fs::write(path, content).unwrap();
+    }
+
+    fn path_string(path: PathBuf) -> String {
+        path.to_string_lossy().to_string()
+    }
+
+    #[test]
+    fn resolves_imports_in_include_path_order() {
+        let root: PathBuf =
+            std::env::temp_dir().join(format!("protoweld-imports-{}", std::process::id()));
+        write_proto(&root.join("app/main.proto"), &["common.proto"]);
+        write_proto(&root.join("first/common.proto"), &["nested/types.proto"]);
+        write_proto(&root.join("second/common.proto"), &[]);
+        write_proto(
+            &root.join("second/nested/types.proto"),
+            &["google/protobuf/any.proto"],
+        );
+
+        let resolved = resolve_transitive_imports(
+            &UnixManager,
+            &[path_string(root.join("app/main.proto"))],
+            &[root.join("first"), root.join("second")],
+        );
+        let _ = fs::remove_dir_all(&root);
+
+        assert_eq!(
+            resolved.unwrap(),
+            vec![
+                path_string(root.join("app/main.proto")),
+                path_string(root.join("first/common.proto")),
+                path_string(root.join("second/nested/types.proto")),
+            ]
+        );
+    }
+
+    #[test]
+    fn fails_on_imports_missing_from_every_include_path() {
+        let root: PathBuf =
+            std::env::temp_dir().join(format!("protoweld-missing-imports-{}", std::process::id()));
+        write_proto(&root.join("app/main.proto"), &["../app/./missing.proto"]);
+
+        let resolved = resolve_transitive_imports(
+            &UnixManager,
+            &[path_string(root.join("app/main.proto"))],
+            &[root.join("app")],
+        );
+        let _ = fs::remove_dir_all(&root);
+
+        let error: String = resolved.unwrap_err();
+        assert!(error.contains("Import ../app/./missing.proto of"));
+        assert!(error.contains(&path_string(root.join("app"))));
+    }
+}
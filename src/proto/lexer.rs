@@ -0,0 +1,222 @@
+//! Tokenizer for proto sources.
+
+/// Kind of a token of a proto source.
+#[derive(Debug, PartialEq, Clone)]
+pub enum TokenKind {
+    /// Identifier, keyword or dotted name (e.g., `message`, `google.protobuf.Any`)
+    Identifier(String),
+    /// Content of a string literal, with escapes resolved
+    StringLiteral(String),
+    /// Integer or floating point literal, without sign (e.g., `42`, `0x1F`, `1e-5`)
+    Number(String),
+    /// Any other single character (e.g., `=`, `;`, `{`)
+    Symbol(char),
+}
+
+/// A token along with the line it starts on.
+#[derive(Debug, PartialEq, Clone)]
+pub struct Token {
+    /// What the token is
+    pub kind: TokenKind,
+    /// Line of the token, starting at 1
+    pub line: usize,
+}
+
+/// Splits a proto source into tokens, dropping whitespace and comments.
+///
+/// # Arguments
+///
+/// * `content` - Source of a `.proto` file
+///
+/// # Returns
+///
+/// * `Ok(Vec<Token>)` - Tokens in source order
+/// * `Err(String)` - A string literal or block comment is not terminated
+pub fn tokenize(content: &str) -> Result<Vec<Token>, String> {
+    let chars: Vec<char> = content.chars().collect();
+    let mut tokens: Vec<Token> = Vec::new();
+    let mut index = 0;
+    let mut line = 1;
+
+    while index < chars.len() {
+        let current = chars[index];
+        let next = chars.get(index + 1).copied();
+
+        match current {
+            '\n' => {
+                line += 1;
+                index += 1;
+            }
+            c if c.is_whitespace() => index += 1,
+            '/' if next == Some('/') => {
+                while index < chars.len() && chars[index] != '\n' {
+                    index += 1;
+                }
+            }
+            '/' if next == Some('*') => {
+                let start_line = line;
+                index += 2;
+                loop {
+                    match (chars.get(index), chars.get(index + 1)) {
+                        (Some('*'), Some('/')) => break,
+                        (Some(c), _) => {
+                            if *c == '\n' {
+                                line += 1;
+                            }
+                            index += 1;
+                        }
+                        (None, _) => {
+                            return Err(format!("line {}: unterminated block comment", start_line))
+                        }
+                    }
+                }
+                index += 2;
+            }
+            '"' | '\'' => {
+                let start_line = line;
+                let mut text = String::new();
+                index += 1;
+                loop {
+                    match chars.get(index) {
+                        Some(c) if *c == current => break,
+                        Some('\n') | None => {
+                            return Err(format!("line {}: unterminated string literal", start_line))
+                        }
+                        Some('\\') => {
+                            index += 1;
+                            match chars.get(index) {
+                                Some('n') => text.push('\n'),
+                                Some('t') => text.push('\t'),
+                                Some('r') => text.push('\r'),
+                                Some('0') => text.push('\0'),
+                                Some(escaped) => text.push(*escaped),
+                                None => {
+                                    return Err(format!(
+                                        "line {}: unterminated string literal",
+                                        start_line
+                                    ))
+                                }
+                            }
+                            index += 1;
+                        }
+                        Some(c) => {
+                            text.push(*c);
+                            index += 1;
+                        }
+                    }
+                }
+                index += 1;
+                tokens.push(Token {
+                    kind: TokenKind::StringLiteral(text),
+                    line: start_line,
+                });
+            }
+            c if c.is_ascii_digit() || (c == '.' && next.is_some_and(|n| n.is_ascii_digit())) => {
+                let mut number = String::new();
+                while let Some(c) = chars.get(index) {
+                    let exponent_sign = (*c == '+' || *c == '-')
+                        && number.ends_with(['e', 'E'])
+                        && !number.starts_with("0x")
+                        && !number.starts_with("0X");
+                    if c.is_ascii_alphanumeric() || *c == '.' || exponent_sign {
+                        number.push(*c);
+                        index += 1;
+                    } else {
+                        break;
+                    }
+                }
+                tokens.push(Token {
+                    kind: TokenKind::Number(number),
+                    line,
+                });
+            }
+            c if is_identifier_start(c) || (c == '.' && next.is_some_and(is_identifier_start)) => {
+                let mut identifier = String::new();
+                while let Some(c) = chars.get(index) {
+                    if is_identifier_start(*c) || c.is_ascii_digit() || *c == '.' {
+                        identifier.push(*c);
+                        index += 1;
+                    } else {
+                        break;
+                    }
+                }
+                tokens.push(Token {
+                    kind: TokenKind::Identifier(identifier),
+                    line,
+                });
+            }
+            c => {
+                tokens.push(Token {
+                    kind: TokenKind::Symbol(c),
+                    line,
+                });
+                index += 1;
+            }
+        }
+    }
+
+    Ok(tokens)
+}
+
+/// Checks whether a character can start an identifier.
+fn is_identifier_start(c: char) -> bool {
+    c.is_ascii_alphabetic() || c == '_'
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn kinds(content: &str) -> Vec<TokenKind> {
+        tokenize(content)
+            .unwrap()
+            .into_iter()
+            .map(|token| token.kind)
+            .collect()
+    }
+
+    #[test]
+    fn drops_line_and_block_comments() {
+        let tokens = kinds("// this package handles users\n/* package other; */ package users;");
+        assert_eq!(
+            tokens,
+            vec![
+                TokenKind::Identifier(String::from("package")),
+                TokenKind::Identifier(String::from("users")),
+                TokenKind::Symbol(';'),
+            ]
+        );
+    }
+
+    #[test]
+    fn keeps_semicolons_and_escapes_inside_strings() {
+        let tokens = kinds(r#"option go_package = "a;b\"c";"#);
+        assert_eq!(tokens[3], TokenKind::StringLiteral(String::from("a;b\"c")));
+        assert_eq!(tokens[4], TokenKind::Symbol(';'));
+    }
+
+    #[test]
+    fn tracks_lines_across_comments() {
+        let tokens = tokenize("/* one\ntwo */\nsyntax").unwrap();
+        assert_eq!(tokens[0].line, 3);
+    }
+
+    #[test]
+    fn reads_numbers_and_dotted_names() {
+        let tokens = kinds("1e-5 0x1F .google.protobuf.Any");
+        assert_eq!(
+            tokens,
+            vec![
+                TokenKind::Number(String::from("1e-5")),
+                TokenKind::Number(String::from("0x1F")),
+                TokenKind::Identifier(String::from(".google.protobuf.Any")),
+            ]
+        );
+    }
+
+    #[test]
+    fn rejects_unterminated_literals() {
+        assert!(tokenize("package \"users;\n").is_err());
+        assert!(tokenize("/* package users;").is_err());
+    }
+}
@@ -0,0 +1,10 @@
+//! Proto source analysis module.
+//!
+//! This module reads `.proto` files directly, without going through `protoc`,
+//! to discover the information Protoweld needs before compiling them (such as
+//! the package, options and imports of each one).
+
+pub mod ast;
+pub mod imports;
+pub mod lexer;
+pub mod parser;
@@ -0,0 +1,730 @@
+//! Recursive descent parser building a [`ProtoFile`] from a proto source.
+//!
+//! The parser follows the proto2, proto3 and editions grammars closely enough
+//! to find every declaration reliably, without validating types or numbers.
+
+use std::{fs, path::PathBuf};
+
+use crate::proto::{
+    ast::{
+        Enum, EnumValue, Field, FieldLabel, Import, ImportKind, Message, Method, Oneof, ProtoFile,
+        ProtoOption, Service,
+    },
+    lexer::{tokenize, Token, TokenKind},
+};
+
+/// Parses the source of a proto file.
+///
+/// # Arguments
+///
+/// * `content` - Source of a `.proto` file
+///
+/// # Returns
+///
+/// * `Ok(ProtoFile)` - The declarations of the file
+/// * `Err(String)` - Syntax error, prefixed with its line (e.g., `line 3: expected ';', found '}'`)
+pub fn parse(content: &str) -> Result<ProtoFile, String> {
+    let mut parser = Parser {
+        tokens: tokenize(content)?,
+        index: 0,
+    };
+
+    parser.file()
+}
+
+/// Reads and parses a proto file.
+///
+/// # Arguments
+///
+/// * `proto` - Path to the `.proto` file
+///
+/// # Returns
+///
+/// * `Ok(ProtoFile)` - The declarations of the file
+/// * `Err(String)` - The file can't be read or has a syntax error
+pub fn parse_file(proto: &PathBuf) -> Result<ProtoFile, String> {
+    let content = match fs::read_to_string(proto) {
+        Ok(content) => content,
+        Err(error) => return Err(format!("Failed to read {}: {}", proto.display(), error)),
+    };
+
+    parse(&content).map_err(|error| format!("Failed to parse {}: {}", proto.display(), error))
+}
+
+/// Cursor over the tokens of a proto source.
+struct Parser {
+    tokens: Vec<Token>,
+    index: usize,
+}
+
+impl Parser {
+    /// Parses the top-level statements of a file.
+    fn file(&mut self) -> Result<ProtoFile, String> {
+        let mut file = ProtoFile::default();
+
+        while self.peek().is_some() {
+            if self.consume_symbol(';') {
+                continue;
+            }
+
+            match self.identifier_ahead(0).as_deref() {
+                Some("syntax") => file.syntax = Some(self.version_statement()?),
+                Some("edition") => file.edition = Some(self.version_statement()?),
+                Some("package") => {
+                    self.index += 1;
+                    file.package = Some(self.expect_identifier()?);
+                    self.expect_symbol(';')?;
+                }
+                Some("import") => file.imports.push(self.import()?),
+                Some("option") => file.options.push(self.option_statement()?),
+                Some("message") => file.messages.push(self.message()?),
+                Some("enum") => file.enums.push(self.enumeration()?),
+                Some("service") => file.services.push(self.service()?),
+                Some("extend") => self.extend()?,
+                _ => return Err(self.error("a top-level declaration")),
+            }
+        }
+
+        Ok(file)
+    }
+
+    /// Parses `syntax = "..."`; or `edition = "...";` and returns its value.
+    fn version_statement(&mut self) -> Result<String, String> {
+        self.index += 1;
+        self.expect_symbol('=')?;
+        let value = self.string()?;
+        self.expect_symbol(';')?;
+        Ok(value)
+    }
+
+    /// Parses `import [public|weak] "path";`.
+    fn import(&mut self) -> Result<Import, String> {
+        self.index += 1;
+        let kind = match self.identifier_ahead(0).as_deref() {
+            Some("public") => ImportKind::Public,
+            Some("weak") => ImportKind::Weak,
+            _ => ImportKind::Default,
+        };
+        if kind != ImportKind::Default {
+            self.index += 1;
+        }
+
+        let path = self.string()?;
+        self.expect_symbol(';')?;
+        Ok(Import { path, kind })
+    }
+
+    /// Parses `option name = constant;`.
+    fn option_statement(&mut self) -> Result<ProtoOption, String> {
+        self.index += 1;
+        let option = self.option()?;
+        self.expect_symbol(';')?;
+        Ok(option)
+    }
+
+    /// Parses `name = constant`, as found in option statements and option lists.
+    fn option(&mut self) -> Result<ProtoOption, String> {
+        let mut name = String::new();
+        loop {
+            match self.peek() {
+                Some(TokenKind::Symbol('=')) => break,
+                Some(TokenKind::Identifier(part)) => name.push_str(part),
+                Some(TokenKind::Symbol(symbol)) if "().".contains(*symbol) => name.push(*symbol),
+                _ => return Err(self.error("an option name")),
+            }
+            self.index += 1;
+        }
+
+        if name.is_empty() {
+            return Err(self.error("an option name"));
+        }
+
+        self.expect_symbol('=')?;
+        let value = self.constant()?;
+        Ok(ProtoOption { name, value })
+    }
+
+    /// Parses `[name = constant, ...]` if present.
+    fn option_list(&mut self) -> Result<Vec<ProtoOption>, String> {
+        let mut options: Vec<ProtoOption> = Vec::new();
+        if !self.consume_symbol('[') {
+            return Ok(options);
+        }
+
+        loop {
+            options.push(self.option()?);
+            if self.consume_symbol(']') {
+                return Ok(options);
+            }
+            self.expect_symbol(',')?;
+        }
+    }
+
+    /// Parses a constant: strings, numbers with sign, identifiers or `{ ... }` aggregates.
+    fn constant(&mut self) -> Result<String, String> {
+        match self.peek() {
+            Some(TokenKind::StringLiteral(_)) => self.string(),
+            Some(TokenKind::Symbol('{')) => self.aggregate(),
+            Some(TokenKind::Symbol(sign)) if *sign == '-' || *sign == '+' => {
+                let sign = *sign;
+                self.index += 1;
+                match self.next()? {
+                    TokenKind::Number(value) | TokenKind::Identifier(value) => {
+                        Ok(format!("{}{}", sign, value))
+                    }
+                    _ => Err(self.error_at(self.index - 1, "a number")),
+                }
+            }
+            Some(TokenKind::Number(value)) | Some(TokenKind::Identifier(value)) => {
+                let value = value.clone();
+                self.index += 1;
+                Ok(value)
+            }
+            _ => Err(self.error("a constant")),
+        }
+    }
+
+    /// Parses a `{ ... }` aggregate value and returns it as written (tokens joined by spaces).
+    fn aggregate(&mut self) -> Result<String, String> {
+        let start = self.index;
+        self.skip_block()?;
+
+        Ok(self.tokens[start..self.index]
+            .iter()
+            .map(|token| match &token.kind {
+                TokenKind::StringLiteral(text) => format!("{:?}", text),
+                TokenKind::Identifier(text) | TokenKind::Number(text) => text.clone(),
+                TokenKind::Symbol(symbol) => symbol.to_string(),
+            })
+            .collect::<Vec<String>>()
+            .join(" "))
+    }
+
+    /// Parses `message Name { ... }`.
+    fn message(&mut self) -> Result<Message, String> {
+        self.index += 1;
+        let name = self.expect_identifier()?;
+        self.message_body(name)
+    }
+
+    /// Parses the `{ ... }` body of a message (or proto2 group).
+    fn message_body(&mut self, name: String) -> Result<Message, String> {
+        let mut message = Message {
+            name,
+            ..Default::default()
+        };
+
+        self.expect_symbol('{')?;
+        while !self.consume_symbol('}') {
+            if self.consume_symbol(';') {
+                continue;
+            }
+
+            let declaration = self.declaration_ahead();
+            match declaration.as_deref() {
+                Some("message") => message.messages.push(self.message()?),
+                Some("enum") => message.enums.push(self.enumeration()?),
+                Some("extend") => self.extend()?,
+                Some("option") => message.options.push(self.option_statement()?),
+                Some("oneof") => self.oneof(&mut message)?,
+                Some("reserved") | Some("extensions") => self.skip_statement()?,
+                _ => self.field(&mut message, None)?,
+            }
+        }
+
+        Ok(message)
+    }
+
+    /// Parses `oneof name { fields }` into `message`.
+    fn oneof(&mut self, message: &mut Message) -> Result<(), String> {
+        self.index += 1;
+        let name = self.expect_identifier()?;
+        let mut oneof = Oneof {
+            name: name.clone(),
+            ..Default::default()
+        };
+
+        self.expect_symbol('{')?;
+        while !self.consume_symbol('}') {
+            if self.consume_symbol(';') {
+                continue;
+            }
+
+            if self.identifier_ahead(0).as_deref() == Some("option") {
+                self.option_statement()?;
+                continue;
+            }
+
+            let fields_before = message.fields.len();
+            self.field(message, Some(&name))?;
+            oneof.fields.extend(
+                message.fields[fields_before..]
+                    .iter()
+                    .map(|field| field.name.clone()),
+            );
+        }
+
+        message.oneofs.push(oneof);
+        Ok(())
+    }
+
+    /// Parses a field (`[label] type name = number [options];`), a map field or
+    /// a proto2 group, adding it to `message`.
+    fn field(&mut self, message: &mut Message, oneof: Option<&String>) -> Result<(), String> {
+        let label = match self.identifier_ahead(0).as_deref() {
+            Some("optional") if self.identifier_ahead(1).is_some() => Some(FieldLabel::Optional),
+            Some("required") if self.identifier_ahead(1).is_some() => Some(FieldLabel::Required),
+            Some("repeated") if self.identifier_ahead(1).is_some() => Some(FieldLabel::Repeated),
+            _ => None,
+        };
+        if label.is_some() {
+            self.index += 1;
+        }
+
+        // proto2 groups declare a nested message and a field at once
+        if self.identifier_ahead(0).as_deref() == Some("group")
+            && self.identifier_ahead(1).is_some()
+        {
+            self.index += 1;
+            let group_name = self.expect_identifier()?;
+            self.expect_symbol('=')?;
+            let number = self.field_number()?;
+            let options = self.option_list()?;
+            let group = self.message_body(group_name.clone())?;
+
+            message.fields.push(Field {
+                name: group_name.to_lowercase(),
+                type_name: group_name,
+                number,
+                label,
+                oneof: oneof.cloned(),
+                options,
+            });
+            message.messages.push(group);
+            return Ok(());
+        }
+
+        let type_name = self.field_type()?;
+        let name = self.expect_identifier()?;
+        self.expect_symbol('=')?;
+        let number = self.field_number()?;
+        let options = self.option_list()?;
+        self.expect_symbol(';')?;
+
+        message.fields.push(Field {
+            name,
+            type_name,
+            number,
+            label,
+            oneof: oneof.cloned(),
+            options,
+        });
+        Ok(())
+    }
+
+    /// Parses a field type, including `map<K, V>`.
+    fn field_type(&mut self) -> Result<String, String> {
+        let type_name = self.expect_identifier()?;
+        if type_name != "map" || !self.consume_symbol('<') {
+            return Ok(type_name);
+        }
+
+        let key = self.expect_identifier()?;
+        self.expect_symbol(',')?;
+        let value = self.expect_identifier()?;
+        self.expect_symbol('>')?;
+        Ok(format!("map<{}, {}>", key, value))
+    }
+
+    /// Parses a field number.
+    fn field_number(&mut self) -> Result<String, String> {
+        match self.next()? {
+            TokenKind::Number(number) => Ok(number),
+            _ => Err(self.error_at(self.index - 1, "a field number")),
+        }
+    }
+
+    /// Parses `enum Name { ... }`.
+    fn enumeration(&mut self) -> Result<Enum, String> {
+        self.index += 1;
+        let mut enumeration = Enum {
+            name: self.expect_identifier()?,
+            ..Default::default()
+        };
+
+        self.expect_symbol('{')?;
+        while !self.consume_symbol('}') {
+            if self.consume_symbol(';') {
+                continue;
+            }
+
+            match self.declaration_ahead().as_deref() {
+                Some("option") => enumeration.options.push(self.option_statement()?),
+                Some("reserved") => self.skip_statement()?,
+                _ => {
+                    let name = self.expect_identifier()?;
+                    self.expect_symbol('=')?;
+                    let number = self.constant()?;
+                    self.option_list()?;
+                    self.expect_symbol(';')?;
+                    enumeration.values.push(EnumValue { name, number });
+                }
+            }
+        }
+
+        Ok(enumeration)
+    }
+
+    /// Parses `service Name { rpc ... }`.
+    fn service(&mut self) -> Result<Service, String> {
+        self.index += 1;
+        let mut service = Service {
+            name: self.expect_identifier()?,
+            ..Default::default()
+        };
+
+        self.expect_symbol('{')?;
+        while !self.consume_symbol('}') {
+            if self.consume_symbol(';') {
+                continue;
+            }
+
+            match self.identifier_ahead(0).as_deref() {
+                Some("option") => service.options.push(self.option_statement()?),
+                Some("rpc") => service.methods.push(self.method()?),
+                _ => return Err(self.error("'rpc' or 'option'")),
+            }
+        }
+
+        Ok(service)
+    }
+
+    /// Parses `rpc Name ([stream] Request) returns ([stream] Response) (; | { options })`.
+    fn method(&mut self) -> Result<Method, String> {
+        self.index += 1;
+        let name = self.expect_identifier()?;
+        let (client_streaming, input_type) = self.method_type()?;
+
+        if self.expect_identifier()? != "returns" {
+            return Err(self.error_at(self.index - 1, "'returns'"));
+        }
+        let (server_streaming, output_type) = self.method_type()?;
+
+        let mut options: Vec<ProtoOption> = Vec::new();
+        if self.consume_symbol('{') {
+            while !self.consume_symbol('}') {
+                if self.consume_symbol(';') {
+                    continue;
+                }
+                match self.identifier_ahead(0).as_deref() {
+                    Some("option") => options.push(self.option_statement()?),
+                    _ => return Err(self.error("'option'")),
+                }
+            }
+        } else {
+            self.expect_symbol(';')?;
+        }
+
+        Ok(Method {
+            name,
+            input_type,
+            output_type,
+            client_streaming,
+            server_streaming,
+            options,
+        })
+    }
+
+    /// Parses `([stream] Type)` and returns whether it streams along with the type.
+    fn method_type(&mut self) -> Result<(bool, String), String> {
+        self.expect_symbol('(')?;
+        let streaming = self.identifier_ahead(0).as_deref() == Some("stream")
+            && self.identifier_ahead(1).is_some();
+        if streaming {
+            self.index += 1;
+        }
+
+        let type_name = self.expect_identifier()?;
+        self.expect_symbol(')')?;
+        Ok((streaming, type_name))
+    }
+
+    /// Skips `extend Type { fields }`, whose fields belong to another message.
+    fn extend(&mut self) -> Result<(), String> {
+        self.index += 1;
+        self.expect_identifier()?;
+        self.skip_block()
+    }
+
+    /// Skips a statement up to its `;` (e.g., `reserved 2, 15, 9 to 11;`).
+    fn skip_statement(&mut self) -> Result<(), String> {
+        while !self.consume_symbol(';') {
+            match self.peek() {
+                Some(TokenKind::Symbol('{')) | Some(TokenKind::Symbol('}')) | None => {
+                    return Err(self.error("';'"))
+                }
+                _ => self.index += 1,
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Skips a `{ ... }` block, including nested blocks.
+    fn skip_block(&mut self) -> Result<(), String> {
+        self.expect_symbol('{')?;
+        let mut depth = 1;
+        while depth > 0 {
+            match self.next()? {
+                TokenKind::Symbol('{') => depth += 1,
+                TokenKind::Symbol('}') => depth -= 1,
+                _ => {}
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Parses one or more adjacent string literals, which are concatenated.
+    fn string(&mut self) -> Result<String, String> {
+        let mut value = match self.next()? {
+            TokenKind::StringLiteral(text) => text,
+            _ => return Err(self.error_at(self.index - 1, "a string")),
+        };
+
+        while let Some(TokenKind::StringLiteral(text)) = self.peek() {
+            value.push_str(text);
+            self.index += 1;
+        }
+
+        Ok(value)
+    }
+
+    /// Returns the keyword starting a declaration (`message Name {`, `option x =`,
+    /// `reserved ...`), so fields named like keywords are parsed as fields.
+    fn declaration_ahead(&self) -> Option<String> {
+        let keyword = self.identifier_ahead(0)?;
+        let is_declaration = match keyword.as_str() {
+            "message" | "enum" | "extend" | "oneof" => {
+                self.identifier_ahead(1).is_some()
+                    && matches!(self.peek_at(2), Some(TokenKind::Symbol('{')))
+            }
+            "option" => !matches!(self.peek_at(1), Some(TokenKind::Symbol('='))),
+            "reserved" | "extensions" => !matches!(self.peek_at(2), Some(TokenKind::Symbol('='))),
+            _ => false,
+        };
+
+        match is_declaration {
+            true => Some(keyword),
+            false => None,
+        }
+    }
+
+    /// Returns the identifier `offset` tokens ahead, if that token is one.
+    fn identifier_ahead(&self, offset: usize) -> Option<String> {
+        match self.peek_at(offset) {
+            Some(TokenKind::Identifier(identifier)) => Some(identifier.clone()),
+            _ => None,
+        }
+    }
+
+    /// Consumes an identifier.
+    fn expect_identifier(&mut self) -> Result<String, String> {
+        match self.next()? {
+            TokenKind::Identifier(identifier) => Ok(identifier),
+            _ => Err(self.error_at(self.index - 1, "an identifier")),
+        }
+    }
+
+    /// Consumes the given symbol.
+    fn expect_symbol(&mut self, symbol: char) -> Result<(), String> {
+        match self.consume_symbol(symbol) {
+            true => Ok(()),
+            false => Err(self.error(&format!("'{}'", symbol))),
+        }
+    }
+
+    /// Consumes the given symbol if it is the next token.
+    fn consume_symbol(&mut self, symbol: char) -> bool {
+        if self.peek() == Some(&TokenKind::Symbol(symbol)) {
+            self.index += 1;
+            return true;
+        }
+
+        false
+    }
+
+    /// Consumes the next token.
+    fn next(&mut self) -> Result<TokenKind, String> {
+        match self.tokens.get(self.index) {
+            Some(token) => {
+                self.index += 1;
+                Ok(token.kind.clone())
+            }
+            None => Err(self.error("more input")),
+        }
+    }
+
+    /// Returns the next token without consuming it.
+    fn peek(&self) -> Option<&TokenKind> {
+        self.peek_at(0)
+    }
+
+    /// Returns the token `offset` positions ahead without consuming it.
+    fn peek_at(&self, offset: usize) -> Option<&TokenKind> {
+        self.tokens
+            .get(self.index + offset)
+            .map(|token| &token.kind)
+    }
+
+    /// Builds an error for the next token.
+    fn error(&self, expected: &str) -> String {
+        self.error_at(self.index, expected)
+    }
+
+    /// Builds an error for the token at `index`.
+    fn error_at(&self, index: usize, expected: &str) -> String {
+        match self.tokens.get(index) {
+            Some(token) => format!(
+                "line {}: expected {}, found {}",
+                token.line,
+                expected,
+                match &token.kind {
+                    TokenKind::Identifier(text) | TokenKind::Number(text) => format!("'{}'", text),
+                    TokenKind::StringLiteral(text) => format!("{:?}", text),
+                    TokenKind::Symbol(symbol) => format!("'{}'", symbol),
+                }
+            ),
+            None => format!(
+                "line {}: expected {}, found end of file",
+                self.tokens.last().map(|token| token.line).unwrap_or(1),
+                expected
+            ),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::proto::ast::ImportKind;
+
+    #[test]
+    fn ignores_package_in_comments_and_field_names() {
+        let file = parse(
+            "// this package handles users\n\
+             syntax = \"proto3\";\n\
+             package users.v1;\n\
+             message User { int64 package_id = 1; }\n",
+        )
+        .unwrap();
+
+        assert_eq!(file.package.as_deref(), Some("users.v1"));
+        assert_eq!(file.messages[0].fields[0].name, "package_id");
+    }
+
+    #[test]
+    fn keeps_string_options_with_semicolons() {
+        let file = parse(
+            "syntax = \"proto3\";\n\
+             package users;\n\
+             option go_package = \"github.com/acme/users;userspb\";\n",
+        )
+        .unwrap();
+
+        assert_eq!(
+            file.option("go_package").map(String::as_str),
+            Some("github.com/acme/users;userspb")
+        );
+    }
+
+    #[test]
+    fn parses_custom_options_with_aggregate_values() {
+        let file = parse(
+            "syntax = \"proto3\";\n\
+             package users;\n\
+             option (acme.custom).x = { name: \"a;b\" size: 2 };\n\
+             option java_package = \"com.acme.users\";\n",
+        )
+        .unwrap();
+
+        assert_eq!(
+            file.option("(acme.custom).x").map(String::as_str),
+            Some("{ name : \"a;b\" size : 2 }")
+        );
+        assert_eq!(
+            file.option("java_package").map(String::as_str),
+            Some("com.acme.users")
+        );
+    }
+
+    #[test]
+    fn parses_nested_messages_and_enums() {
+        let file = parse(
+            "syntax = \"proto3\";\n\
+             package users;\n\
+             message User {\n\
+               message Address { string city = 1; }\n\
+               enum Role { ROLE_UNSPECIFIED = 0; ADMIN = 1; }\n\
+               Address address = 1;\n\
+               map<string, Role> roles = 2;\n\
+               oneof contact { string email = 3; string phone = 4; }\n\
+             }\n\
+             enum Status { STATUS_UNSPECIFIED = 0; }\n\
+             service Users { rpc Get(User) returns (stream User); }\n",
+        )
+        .unwrap();
+
+        let user = &file.messages[0];
+        assert_eq!(user.messages[0].name, "Address");
+        assert_eq!(user.enums[0].name, "Role");
+        assert_eq!(user.enums[0].values.len(), 2);
+        assert!(user.fields[1].is_map());
+        assert_eq!(user.oneofs[0].fields, vec!["email", "phone"]);
+        assert_eq!(file.enums[0].name, "Status");
+        assert!(file.has_services());
+        assert!(file.services[0].methods[0].server_streaming);
+        assert!(!file.services[0].methods[0].client_streaming);
+    }
+
+    #[test]
+    fn parses_editions() {
+        let file = parse("edition = \"2023\";\npackage users;\n").unwrap();
+
+        assert_eq!(file.edition.as_deref(), Some("2023"));
+        assert_eq!(file.syntax, None);
+        assert_eq!(file.package.as_deref(), Some("users"));
+    }
+
+    #[test]
+    fn parses_import_kinds() {
+        let file = parse(
+            "syntax = \"proto3\";\n\
+             import \"google/protobuf/any.proto\";\n\
+             import public \"common/ids.proto\";\n\
+             import weak \"legacy/old.proto\";\n",
+        )
+        .unwrap();
+
+        let imports: Vec<(&str, ImportKind)> = file
+            .imports
+            .iter()
+            .map(|import| (import.path.as_str(), import.kind))
+            .collect();
+        assert_eq!(
+            imports,
+            vec![
+                ("google/protobuf/any.proto", ImportKind::Default),
+                ("common/ids.proto", ImportKind::Public),
+                ("legacy/old.proto", ImportKind::Weak),
+            ]
+        );
+    }
+
+    #[test]
+    fn reports_the_line_of_syntax_errors() {
+        let error = parse("syntax = \"proto3\";\npackage users\nmessage User {}\n").unwrap_err();
+
+        assert!(error.contains("line 3"), "{}", error);
+    }
+}
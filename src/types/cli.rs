@@ -16,4 +16,19 @@ pub struct Cli {
     /// that need to have their `.proto` files compiled.
     #[arg(short, long)]
     pub filename: String,
+
+    /// Maximum number of projects compiled at the same time.
+    ///
+    /// Projects writing to overlapping output folders still run one after
+    /// another, in configuration order. Overrides the `jobs` setting of the
+    /// configuration file (default: 1).
+    #[arg(short, long, value_parser = clap::value_parser!(u64).range(1..))]
+    pub jobs: Option<u64>,
+
+    /// Compile every project even when some fail, then print a summary of
+    /// the outcome of each one.
+    ///
+    /// Protoweld still exits with an error if any project failed.
+    #[arg(short, long)]
+    pub keep_going: bool,
 }